@@ -0,0 +1,129 @@
+use std::sync::{OnceLock, RwLock};
+
+use crate::settings::{read_key_values, save_file};
+
+/// Shared by every game
+pub const ACCESSIBILITY_FILE: &str = "save/accessibility.txt";
+/// Multiples of the normal text size players can pick from
+pub const TEXT_SCALES: &[f32] = &[1.0, 1.25, 1.5];
+
+/// Colorblind-safe palettes, manifest entries list a tint for each one
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Palette {
+    /// For protanopia and deuteranopia
+    RedGreen,
+    /// For tritanopia
+    BlueYellow,
+}
+
+impl Palette {
+    pub const ALL: &'static [Self] = &[Palette::RedGreen, Palette::BlueYellow];
+
+    /// Name used in the settings file and manifests
+    pub fn id(&self) -> &'static str {
+        match self {
+            Palette::RedGreen => "red_green",
+            Palette::BlueYellow => "blue_yellow",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|p| p.id() == id)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Accessibility {
+    pub high_contrast: bool,
+    /// Sprites keep their own colors without one
+    pub palette: Option<Palette>,
+    /// No flashing effects or camera shake
+    pub reduced_motion: bool,
+    /// One of `TEXT_SCALES`
+    pub text_scale: f32,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Self {
+            high_contrast: false,
+            palette: None,
+            reduced_motion: false,
+            text_scale: TEXT_SCALES[0],
+        }
+    }
+}
+
+impl Accessibility {
+    /// Reads `<setting> <value>` lines, unknown settings and values are skipped
+    fn load() -> Self {
+        let mut settings = Self::default();
+        for (key, value) in read_key_values(ACCESSIBILITY_FILE) {
+            let on = value == "on";
+            match key.as_str() {
+                "high_contrast" => settings.high_contrast = on,
+                "palette" => settings.palette = Palette::from_id(&value),
+                "reduced_motion" => settings.reduced_motion = on,
+                "text_scale" => {
+                    if let Some(scale) = value
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|scale| TEXT_SCALES.contains(scale))
+                    {
+                        settings.text_scale = scale;
+                    }
+                }
+                _ => (),
+            }
+        }
+        settings
+    }
+
+    fn to_text(&self) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        format!(
+            "high_contrast {}\npalette {}\nreduced_motion {}\ntext_scale {}\n",
+            on_off(self.high_contrast),
+            self.palette.map_or("none", |p| p.id()),
+            on_off(self.reduced_motion),
+            self.text_scale
+        )
+    }
+
+    /// The next palette, or none after the last one
+    pub fn next_palette(&self) -> Option<Palette> {
+        match self.palette {
+            None => Palette::ALL.first().copied(),
+            Some(palette) => Palette::ALL
+                .iter()
+                .skip_while(|p| **p != palette)
+                .nth(1)
+                .copied(),
+        }
+    }
+
+    /// The next text scale, wrapping around to the smallest
+    pub fn next_text_scale(&self) -> f32 {
+        TEXT_SCALES
+            .iter()
+            .position(|scale| *scale == self.text_scale)
+            .map_or(TEXT_SCALES[0], |i| TEXT_SCALES[(i + 1) % TEXT_SCALES.len()])
+    }
+}
+
+fn current() -> &'static RwLock<Accessibility> {
+    static CURRENT: OnceLock<RwLock<Accessibility>> = OnceLock::new();
+    CURRENT.get_or_init(|| RwLock::new(Accessibility::load()))
+}
+
+/// The saved settings, defaults if there are none
+pub fn accessibility() -> Accessibility {
+    current().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Switches and saves the settings
+pub fn set_accessibility(settings: Accessibility) {
+    let text = settings.to_text();
+    *current().write().unwrap_or_else(|e| e.into_inner()) = settings;
+    save_file(ACCESSIBILITY_FILE, "accessibility settings", text);
+}
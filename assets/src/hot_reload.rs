@@ -0,0 +1,113 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    time::SystemTime,
+};
+
+/// Polls every file under a directory for changes
+pub struct FileWatcher {
+    root: PathBuf,
+    mtimes: HashMap<PathBuf, SystemTime>,
+}
+
+impl FileWatcher {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        let mut watcher = Self {
+            root: root.as_ref().to_path_buf(),
+            mtimes: HashMap::new(),
+        };
+        watcher.mtimes = watcher.scan();
+        watcher
+    }
+
+    fn scan(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut mtimes = HashMap::new();
+        let mut dirs = vec![self.root.clone()];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                match entry.metadata() {
+                    Ok(meta) if meta.is_dir() => dirs.push(path),
+                    Ok(meta) => {
+                        if let Ok(mtime) = meta.modified() {
+                            mtimes.insert(path, mtime);
+                        }
+                    }
+                    Err(_) => (),
+                }
+            }
+        }
+        mtimes
+    }
+
+    /// Returns files that were added or modified since the last poll
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let mtimes = self.scan();
+        let changed = mtimes
+            .iter()
+            .filter(|(path, mtime)| self.mtimes.get(*path) != Some(mtime))
+            .map(|(path, _)| path.clone())
+            .collect();
+        self.mtimes = mtimes;
+        changed
+    }
+}
+
+/// Reload count of each changed file, and its latest copy once made
+type Versions = Mutex<HashMap<PathBuf, (u32, Option<String>)>>;
+
+fn versions() -> &'static Versions {
+    static VERSIONS: OnceLock<Versions> = OnceLock::new();
+    VERSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Number of times `path` has been reloaded
+pub fn version(path: impl AsRef<Path>) -> u32 {
+    let versions = versions().lock().unwrap_or_else(|e| e.into_inner());
+    versions.get(path.as_ref()).map_or(0, |(v, _)| *v)
+}
+
+/// Marks `path` as changed so `versioned()` returns a new file for it
+pub fn bump_version(path: impl AsRef<Path>) {
+    let mut versions = versions().lock().unwrap_or_else(|e| e.into_inner());
+    let (v, copy) = versions.entry(path.as_ref().to_path_buf()).or_default();
+    *v += 1;
+    *copy = None;
+}
+
+/// The asset manager caches files by path, so once `path` has changed this
+/// returns a copy of it at a new path that will be loaded fresh
+/// Falls back to `path` if the copy can't be made
+pub fn versioned(path: &str) -> String {
+    let mut versions = versions().lock().unwrap_or_else(|e| e.into_inner());
+    let Some((v, copy)) = versions.get_mut(Path::new(path)) else {
+        return path.to_string();
+    };
+    if let Some(copy) = copy {
+        return copy.clone();
+    }
+
+    let mut hasher = DefaultHasher::new();
+    Path::new(path).hash(&mut hasher);
+    let ext = Path::new(path)
+        .extension()
+        .map_or(String::new(), |ext| format!(".{}", ext.to_string_lossy()));
+    let file = std::env::temp_dir()
+        .join("hyperfold_reload")
+        .join(format!("{:016x}_v{v}{ext}", hasher.finish()));
+    let res = fs::create_dir_all(file.parent().unwrap_or(Path::new(".")))
+        .and_then(|_| fs::copy(path, &file));
+    if let Err(e) = res {
+        eprintln!("Could not reload {path}: {e}");
+        return path.to_string();
+    }
+    let file = file.to_string_lossy().to_string();
+    *copy = Some(file.clone());
+    file
+}
@@ -0,0 +1,11 @@
+pub mod accessibility;
+pub mod hot_reload;
+pub mod manifest;
+pub mod piskel;
+pub mod settings;
+pub mod strings;
+
+pub use accessibility::Palette;
+pub use hot_reload::FileWatcher;
+pub use manifest::{AssetEntry, AssetKey, Manifest, ManifestError, Shape};
+pub use piskel::{Piskel, PiskelError};
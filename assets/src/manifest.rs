@@ -0,0 +1,299 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use hyperfold_engine::{
+    framework::render_system::{
+        render_data::{Animation, RenderAsset, RenderDataBuilderTrait},
+        AssetManager, Renderer,
+    },
+    utils::rect::Rect,
+};
+use serde::Deserialize;
+
+use crate::{
+    accessibility::{accessibility, Palette},
+    hot_reload::versioned,
+    piskel::Piskel,
+};
+
+/// Ids of every asset a game uses, usually a fieldless enum
+pub trait AssetKey: Copy + 'static {
+    const ALL: &'static [Self];
+
+    /// The key for this asset in the manifest file
+    fn id(&self) -> &'static str;
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EntryData {
+    path: PathBuf,
+    #[serde(default)]
+    first_frame: usize,
+    /// Defaults to every frame from `first_frame` on for piskels
+    /// Images are a sheet of exactly this many frames, defaulting to 1
+    frames: Option<u32>,
+    /// Defaults to the piskel fps
+    frame_ms: Option<u32>,
+    /// Area of the asset to draw, `[x, y, w, h]`
+    rect: Option<[f32; 4]>,
+    /// Repeats the first frame `[cols, rows]` times
+    tile: Option<[u32; 2]>,
+    shape: Option<Shape>,
+    /// Color for each colorblind palette, by palette id
+    #[serde(default)]
+    palettes: HashMap<String, [u8; 3]>,
+}
+
+/// Pieces generated from the first frame of a strip, see `Piskel::bent()` and `Piskel::capped()`
+#[derive(Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Shape {
+    Bend,
+    Cap,
+}
+
+enum Source {
+    /// Looked up on use so reloads are picked up
+    Piskel,
+    /// A sprite sheet with frames side by side
+    Image,
+}
+
+/// A validated manifest entry
+pub struct AssetEntry {
+    pub path: PathBuf,
+    pub first_frame: usize,
+    pub frames: u32,
+    pub frame_ms: u32,
+    pub rect: Option<Rect>,
+    pub tile: Option<(u32, u32)>,
+    pub shape: Option<Shape>,
+    /// Tints used when a colorblind palette is picked, entries without one keep their colors
+    pub palettes: HashMap<Palette, [u8; 3]>,
+    /// Size of a single frame
+    pub w: u32,
+    pub h: u32,
+    source: Source,
+}
+
+impl AssetEntry {
+    fn load(id: &str, data: EntryData) -> Result<Self, String> {
+        let err = |e: &dyn Display| format!("'{id}': {}: {e}", data.path.display());
+        if !data.path.is_file() {
+            return Err(err(&"File not found"));
+        }
+
+        let is_piskel = data.path.extension().is_some_and(|ext| ext == "piskel");
+        let (source, w, h, frame_count, default_ms) = if is_piskel {
+            let piskel = Piskel::try_get(&data.path).map_err(|e| err(&e))?;
+            let (w, h, n) = (piskel.w, piskel.h, piskel.frame_count());
+            (Source::Piskel, w, h, n, piskel.frame_ms())
+        } else {
+            // The whole image is the sprite sheet
+            let (w, h) = image::image_dimensions(&data.path).map_err(|e| err(&e))?;
+            let n = data.frames.unwrap_or(1);
+            if n == 0 || w % n != 0 {
+                return Err(err(&format!("Width {w} is not a multiple of {n} frames")));
+            }
+            (Source::Image, w / n, h, n, 100)
+        };
+
+        let frames = data
+            .frames
+            .unwrap_or_else(|| frame_count.saturating_sub(data.first_frame as u32));
+        if frames == 0 || data.first_frame as u32 + frames > frame_count {
+            return Err(err(&format!(
+                "Frames {}..{} are outside the {frame_count} frames",
+                data.first_frame,
+                data.first_frame as u32 + frames
+            )));
+        }
+        if (data.tile.is_some() || data.shape.is_some() || !data.palettes.is_empty()) && !is_piskel
+        {
+            return Err(err(&"Only piskels can be tiled, shaped or tinted"));
+        }
+        if data.tile.is_some() && data.shape.is_some() {
+            return Err(err(&"Can't be both tiled and shaped"));
+        }
+        let mut palettes = HashMap::new();
+        for (id, tint) in data.palettes.iter() {
+            let palette =
+                Palette::from_id(id).ok_or_else(|| err(&format!("Unknown palette '{id}'")))?;
+            palettes.insert(palette, *tint);
+        }
+
+        Ok(Self {
+            path: data.path,
+            first_frame: data.first_frame,
+            frames,
+            frame_ms: data.frame_ms.unwrap_or(default_ms),
+            rect: data.rect.map(|[x, y, w, h]| Rect { x, y, w, h }),
+            tile: data.tile.map(|[cols, rows]| (cols, rows)),
+            shape: data.shape,
+            palettes,
+            w,
+            h,
+            source,
+        })
+    }
+
+    /// The current copy of the piskel, which changes when it is hot reloaded
+    fn piskel(&self) -> Arc<Piskel> {
+        Piskel::get(&self.path)
+    }
+
+    /// Tint for the palette picked in the accessibility settings
+    pub fn tint(&self) -> Option<[u8; 3]> {
+        accessibility()
+            .palette
+            .and_then(|palette| self.palettes.get(&palette).copied())
+    }
+
+    fn image(&self, r: &Renderer, am: &mut AssetManager) -> RenderAsset {
+        RenderAsset::from_file(&versioned(&self.path.to_string_lossy()), r, am)
+    }
+
+    /// Looping animation over every frame
+    pub fn animation(&self) -> Animation {
+        Animation::new(self.frames, self.frame_ms)
+    }
+
+    /// Animation that plays every frame once
+    pub fn animation_once(&self) -> Animation {
+        Animation::once(self.frames, self.frame_ms)
+    }
+
+    /// Sprite sheet of every frame, or the tiled or shaped first frame
+    pub fn asset(&self, r: &Renderer, am: &mut AssetManager) -> RenderAsset {
+        self.asset_at(0, false, r, am)
+    }
+
+    /// Like `asset()`, with shaped pieces scrolled by `offset` rows
+    /// `mirrored` makes bends turn clockwise
+    pub fn asset_at(
+        &self,
+        offset: u32,
+        mirrored: bool,
+        r: &Renderer,
+        am: &mut AssetManager,
+    ) -> RenderAsset {
+        let tint = self.tint();
+        let asset = match (&self.source, self.tile) {
+            (Source::Piskel, _) if self.shape == Some(Shape::Bend) => {
+                self.piskel()
+                    .bent_asset(self.first_frame, offset, mirrored, tint, r, am)
+            }
+            (Source::Piskel, _) if self.shape == Some(Shape::Cap) => {
+                self.piskel()
+                    .capped_asset(self.first_frame, offset, tint, r, am)
+            }
+            (Source::Piskel, Some((cols, rows))) => {
+                self.piskel()
+                    .tiled_asset(self.first_frame, cols, rows, tint, r, am)
+            }
+            (Source::Piskel, None) => {
+                self.piskel()
+                    .strip_asset(self.first_frame, self.frames as usize, tint, r, am)
+            }
+            (Source::Image, _) => self.image(r, am),
+        };
+        match self.rect {
+            Some(rect) => asset.with_area(Some(rect)),
+            None => asset,
+        }
+    }
+
+    /// Frame `i` of this entry as a still image
+    pub fn frame_asset(&self, i: u32, r: &Renderer, am: &mut AssetManager) -> RenderAsset {
+        let frame = self.first_frame + (i % self.frames) as usize;
+        match &self.source {
+            Source::Piskel => self.piskel().frame_asset(frame, self.tint(), r, am),
+            Source::Image => self.image(r, am).with_area(Some(Rect {
+                x: (frame as u32 * self.w) as f32,
+                y: 0.0,
+                w: self.w as f32,
+                h: self.h as f32,
+            })),
+        }
+    }
+}
+
+/// Every problem found while loading a manifest
+#[derive(Debug)]
+pub struct ManifestError {
+    pub path: PathBuf,
+    pub errors: Vec<String>,
+}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid asset manifest {}:", self.path.display())?;
+        for e in self.errors.iter() {
+            write!(f, "\n  {e}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Maps each asset id of a game to its file and how to draw it
+pub struct Manifest<K: AssetKey> {
+    entries: HashMap<&'static str, AssetEntry>,
+    _keys: PhantomData<K>,
+}
+
+impl<K: AssetKey> Manifest<K> {
+    /// Loads every asset in the manifest, failing with every missing or invalid asset
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let fail = |errors| ManifestError {
+            path: path.to_path_buf(),
+            errors,
+        };
+        let mut data: HashMap<String, EntryData> = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| serde_json::from_str(&s).map_err(|e| e.to_string()))
+            .map_err(|e| fail(vec![e]))?;
+
+        let mut entries = HashMap::new();
+        let mut errors = Vec::new();
+        for key in K::ALL {
+            let id = key.id();
+            match data.remove(id) {
+                Some(entry) => match AssetEntry::load(id, entry) {
+                    Ok(entry) => {
+                        entries.insert(id, entry);
+                    }
+                    Err(e) => errors.push(e),
+                },
+                None => errors.push(format!("'{id}': Missing from manifest")),
+            }
+        }
+        let mut unknown = data.into_keys().collect::<Vec<_>>();
+        unknown.sort();
+        errors.extend(
+            unknown
+                .into_iter()
+                .map(|id| format!("'{id}': Unknown asset")),
+        );
+
+        match errors.is_empty() {
+            true => Ok(Self {
+                entries,
+                _keys: PhantomData,
+            }),
+            false => Err(fail(errors)),
+        }
+    }
+
+    pub fn get(&self, key: K) -> &AssetEntry {
+        // Every key was checked when loading
+        &self.entries[key.id()]
+    }
+}
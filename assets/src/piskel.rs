@@ -0,0 +1,457 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    f32::consts::FRAC_PI_2,
+    fmt::Display,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hyperfold_engine::framework::render_system::{
+    render_data::{Animation, RenderAsset},
+    AssetManager, Renderer,
+};
+use image::{imageops, ImageError, Rgba, RgbaImage};
+use serde::Deserialize;
+
+use crate::hot_reload;
+
+#[derive(Deserialize)]
+struct PiskelFile {
+    piskel: PiskelData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PiskelData {
+    name: String,
+    fps: u32,
+    width: u32,
+    height: u32,
+    /// Each layer is itself a JSON string
+    layers: Vec<String>,
+    #[serde(default)]
+    hidden_frames: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LayerData {
+    opacity: f32,
+    frame_count: usize,
+    chunks: Vec<ChunkData>,
+}
+
+#[derive(Deserialize)]
+struct ChunkData {
+    /// `layout[col][row]` is the frame drawn at that cell of the chunk's sprite sheet
+    layout: Vec<Vec<usize>>,
+    #[serde(rename = "base64PNG")]
+    base64_png: String,
+}
+
+#[derive(Debug)]
+pub enum PiskelError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Base64(base64::DecodeError),
+    Image(ImageError),
+    /// A chunk's sprite sheet doesn't match its layout
+    Layout(String),
+}
+
+impl Display for PiskelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PiskelError::Io(e) => write!(f, "{e}"),
+            PiskelError::Json(e) => write!(f, "Invalid piskel JSON: {e}"),
+            PiskelError::Base64(e) => write!(f, "Invalid base64 PNG: {e}"),
+            PiskelError::Image(e) => write!(f, "Invalid PNG: {e}"),
+            PiskelError::Layout(e) => write!(f, "Invalid chunk layout: {e}"),
+        }
+    }
+}
+
+impl From<io::Error> for PiskelError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for PiskelError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<base64::DecodeError> for PiskelError {
+    fn from(e: base64::DecodeError) -> Self {
+        Self::Base64(e)
+    }
+}
+
+impl From<ImageError> for PiskelError {
+    fn from(e: ImageError) -> Self {
+        Self::Image(e)
+    }
+}
+
+/// A decoded Piskel project with all layers flattened into one image per frame
+pub struct Piskel {
+    pub path: PathBuf,
+    pub name: String,
+    pub fps: u32,
+    pub w: u32,
+    pub h: u32,
+    /// Visible frames only, hidden frames are dropped
+    pub frames: Vec<RgbaImage>,
+    /// Hot reload version of the file when it was loaded
+    version: u32,
+    /// Generated images already written to the cache, by variant
+    cached: Mutex<HashMap<String, PathBuf>>,
+}
+
+impl Piskel {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PiskelError> {
+        let path = path.as_ref();
+        let file: PiskelFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let data = file.piskel;
+
+        let mut frames = Vec::new();
+        // Layers are stored bottom to top
+        for layer in data.layers.iter() {
+            let layer: LayerData = serde_json::from_str(layer)?;
+            if frames.is_empty() {
+                frames = vec![RgbaImage::new(data.width, data.height); layer.frame_count];
+            }
+            for chunk in layer.chunks.iter() {
+                let png = chunk
+                    .base64_png
+                    .split_once(',')
+                    .map_or(chunk.base64_png.as_str(), |(_, png)| png);
+                let sheet = image::load_from_memory(&STANDARD.decode(png)?)?.into_rgba8();
+                for (col, rows) in chunk.layout.iter().enumerate() {
+                    for (row, i) in rows.iter().enumerate() {
+                        let (x, y) = (col as u32 * data.width, row as u32 * data.height);
+                        if x + data.width > sheet.width() || y + data.height > sheet.height() {
+                            return Err(PiskelError::Layout(format!(
+                                "Frame {i} at ({col}, {row}) is outside the {}x{} sheet",
+                                sheet.width(),
+                                sheet.height()
+                            )));
+                        }
+                        let Some(frame) = frames.get_mut(*i) else {
+                            return Err(PiskelError::Layout(format!(
+                                "Frame {i} is past the frame count {}",
+                                layer.frame_count
+                            )));
+                        };
+                        let mut src =
+                            imageops::crop_imm(&sheet, x, y, data.width, data.height).to_image();
+                        if layer.opacity < 1.0 {
+                            src.pixels_mut().for_each(|p| {
+                                p.0[3] = (p.0[3] as f32 * layer.opacity.max(0.0)) as u8
+                            });
+                        }
+                        imageops::overlay(frame, &src, 0, 0);
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            name: data.name,
+            fps: data.fps,
+            w: data.width,
+            h: data.height,
+            frames: frames
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, frame)| (!data.hidden_frames.contains(&i)).then_some(frame))
+                .collect(),
+            version: hot_reload::version(path),
+            cached: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn cache() -> &'static Mutex<HashMap<PathBuf, Arc<Piskel>>> {
+        static CACHE: OnceLock<Mutex<HashMap<PathBuf, Arc<Piskel>>>> = OnceLock::new();
+        CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    /// Returns the shared copy of the piskel at `path`, loading it on first use
+    /// Failed loads are not cached
+    pub fn try_get(path: impl AsRef<Path>) -> Result<Arc<Self>, PiskelError> {
+        let path = path.as_ref();
+        let mut cache = Self::cache().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(piskel) = cache.get(path) {
+            return Ok(piskel.clone());
+        }
+        let piskel = Arc::new(Self::load(path)?);
+        cache.insert(path.to_path_buf(), piskel.clone());
+        Ok(piskel)
+    }
+
+    /// Like `try_get()` but panics if the file can't be loaded, like a missing image would
+    pub fn get(path: impl AsRef<Path>) -> Arc<Self> {
+        let path = path.as_ref();
+        Self::try_get(path).unwrap_or_else(|e| panic!("Could not load {}: {e}", path.display()))
+    }
+
+    /// Replaces the shared copy of the piskel at `path` with a fresh load
+    /// The old copy is kept if the file can't be loaded
+    pub fn reload(path: impl AsRef<Path>) -> Result<Arc<Self>, PiskelError> {
+        let path = path.as_ref();
+        let piskel = Arc::new(Self::load(path)?);
+        Self::cache()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path.to_path_buf(), piskel.clone());
+        Ok(piskel)
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.frames.len() as u32
+    }
+
+    pub fn frame_ms(&self) -> u32 {
+        1000 / self.fps.max(1)
+    }
+
+    /// Looping animation over every frame
+    pub fn animation(&self) -> Animation {
+        Animation::new(self.frame_count(), self.frame_ms())
+    }
+
+    /// Animation that plays every frame once
+    pub fn animation_once(&self) -> Animation {
+        Animation::once(self.frame_count(), self.frame_ms())
+    }
+
+    /// `count` frames starting at `first` side by side, the sprite sheet layout used by `Animation`
+    pub fn strip(&self, first: usize, count: usize) -> RgbaImage {
+        let mut img = RgbaImage::new(self.w * count.max(1) as u32, self.h);
+        for (i, frame) in self.frames.iter().skip(first).take(count).enumerate() {
+            imageops::replace(&mut img, frame, (i as u32 * self.w) as i64, 0);
+        }
+        img
+    }
+
+    /// One frame repeated `cols` by `rows` times, for textures that scroll
+    pub fn tiled(&self, frame: usize, cols: u32, rows: u32) -> RgbaImage {
+        let mut img = RgbaImage::new(self.w * cols, self.h * rows);
+        if let Some(frame) = self.frames.get(frame) {
+            for x in 0..cols {
+                for y in 0..rows {
+                    imageops::replace(&mut img, frame, (x * self.w) as i64, (y * self.h) as i64);
+                }
+            }
+        }
+        img
+    }
+
+    /// Source pixel for a strip of `frame` scrolled down by `offset` rows, clamped to the frame
+    fn strip_pixel(&self, frame: &RgbaImage, x: u32, y: u32, offset: u32) -> Rgba<u8> {
+        *frame.get_pixel(x.min(self.w - 1), (y + offset) % self.h.max(1))
+    }
+
+    /// A quarter turn of `frame`, as if the strip was bent around the top left corner
+    /// The top of the strip is at the top edge and the bottom at the left edge,
+    /// so a strip moving up turns counter clockwise. `mirrored` turns clockwise instead
+    /// `offset` scrolls it like the area of a tiled strip
+    pub fn bent(&self, frame: usize, offset: u32, mirrored: bool) -> RgbaImage {
+        let w = self.w;
+        let mut img = RgbaImage::new(w, w);
+        let Some(frame) = self.frames.get(frame) else {
+            return img;
+        };
+        for (x, y, p) in img.enumerate_pixels_mut() {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let radius = (px * px + py * py).sqrt();
+            if radius >= w as f32 {
+                continue;
+            }
+            // Stretched so the turn is as long as a frame, matching straight pieces
+            let along = py.atan2(px) / FRAC_PI_2 * self.h as f32;
+            *p = self.strip_pixel(frame, radius as u32, along as u32, offset);
+        }
+        match mirrored {
+            true => imageops::flip_horizontal(&img),
+            false => img,
+        }
+    }
+
+    /// The end of `frame` rounded off, half a frame long with the round end at the bottom
+    pub fn capped(&self, frame: usize, offset: u32) -> RgbaImage {
+        let (w, h) = (self.w, (self.h / 2).max(1));
+        let mut img = RgbaImage::new(w, h);
+        let Some(frame) = self.frames.get(frame) else {
+            return img;
+        };
+        let r = w as f32 / 2.0;
+        for (x, y, p) in img.enumerate_pixels_mut() {
+            let (dx, dy) = (x as f32 + 0.5 - r, (y as f32 + 0.5) * r / h as f32);
+            if dx * dx + dy * dy <= r * r {
+                *p = self.strip_pixel(frame, x, y, offset);
+            }
+        }
+        img
+    }
+
+    /// The asset manager loads images from files, so generated images are written to a cache
+    fn cache_file(&self, variant: &str, img: impl FnOnce() -> RgbaImage) -> PathBuf {
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(file) = cached.get(variant) {
+            return file.clone();
+        }
+
+        let mut hasher = DefaultHasher::new();
+        self.path.hash(&mut hasher);
+        let file = std::env::temp_dir().join("hyperfold_piskel").join(format!(
+            "{:016x}_v{}_{variant}.png",
+            hasher.finish(),
+            self.version
+        ));
+        let res = fs::create_dir_all(file.parent().unwrap_or(Path::new(".")))
+            .map_err(ImageError::IoError)
+            .and_then(|_| img().save(&file));
+        if let Err(e) = res {
+            panic!("Could not write {}: {e}", file.display());
+        }
+        cached.insert(variant.to_string(), file.clone());
+        file
+    }
+
+    /// `tint` recolors the image, see `tinted()`
+    fn asset_from(
+        &self,
+        variant: &str,
+        img: impl FnOnce() -> RgbaImage,
+        tint: Option<[u8; 3]>,
+        r: &Renderer,
+        am: &mut AssetManager,
+    ) -> RenderAsset {
+        let file = match tint {
+            Some(tint @ [red, green, blue]) => self.cache_file(
+                &format!("{variant}_t{red:02x}{green:02x}{blue:02x}"),
+                || tinted(&img(), tint),
+            ),
+            None => self.cache_file(variant, img),
+        };
+        RenderAsset::from_file(&file.to_string_lossy(), r, am)
+    }
+
+    /// Sprite sheet of every frame, pair with `animation()` or `animation_once()`
+    pub fn asset(&self, r: &Renderer, am: &mut AssetManager) -> RenderAsset {
+        self.strip_asset(0, self.frames.len(), None, r, am)
+    }
+
+    /// Sprite sheet of `count` frames starting at `first`
+    pub fn strip_asset(
+        &self,
+        first: usize,
+        count: usize,
+        tint: Option<[u8; 3]>,
+        r: &Renderer,
+        am: &mut AssetManager,
+    ) -> RenderAsset {
+        self.asset_from(
+            &format!("strip{first}_{count}"),
+            || self.strip(first, count),
+            tint,
+            r,
+            am,
+        )
+    }
+
+    /// A single frame as a still image
+    pub fn frame_asset(
+        &self,
+        frame: usize,
+        tint: Option<[u8; 3]>,
+        r: &Renderer,
+        am: &mut AssetManager,
+    ) -> RenderAsset {
+        self.asset_from(
+            &format!("frame{frame}"),
+            || self.tiled(frame, 1, 1),
+            tint,
+            r,
+            am,
+        )
+    }
+
+    /// See `bent()`
+    pub fn bent_asset(
+        &self,
+        frame: usize,
+        offset: u32,
+        mirrored: bool,
+        tint: Option<[u8; 3]>,
+        r: &Renderer,
+        am: &mut AssetManager,
+    ) -> RenderAsset {
+        self.asset_from(
+            &format!("bent{frame}_{offset}{}", if mirrored { "_m" } else { "" }),
+            || self.bent(frame, offset, mirrored),
+            tint,
+            r,
+            am,
+        )
+    }
+
+    /// See `capped()`
+    pub fn capped_asset(
+        &self,
+        frame: usize,
+        offset: u32,
+        tint: Option<[u8; 3]>,
+        r: &Renderer,
+        am: &mut AssetManager,
+    ) -> RenderAsset {
+        self.asset_from(
+            &format!("capped{frame}_{offset}"),
+            || self.capped(frame, offset),
+            tint,
+            r,
+            am,
+        )
+    }
+
+    /// A single frame repeated `cols` by `rows` times
+    pub fn tiled_asset(
+        &self,
+        frame: usize,
+        cols: u32,
+        rows: u32,
+        tint: Option<[u8; 3]>,
+        r: &Renderer,
+        am: &mut AssetManager,
+    ) -> RenderAsset {
+        self.asset_from(
+            &format!("frame{frame}_{cols}x{rows}"),
+            || self.tiled(frame, cols, rows),
+            tint,
+            r,
+            am,
+        )
+    }
+}
+
+/// Recolors `img` with shades of `tint` by brightness, keeping alpha
+/// Sprites stay readable since light and dark pixels keep their contrast
+pub fn tinted(img: &RgbaImage, tint: [u8; 3]) -> RgbaImage {
+    let mut out = img.clone();
+    for p in out.pixels_mut() {
+        let [r, g, b, _] = p.0;
+        let lum = (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0;
+        for (c, t) in p.0.iter_mut().zip(tint) {
+            *c = (t as f32 * (0.3 + 1.4 * lum)).min(255.0) as u8;
+        }
+    }
+    out
+}
@@ -0,0 +1,45 @@
+use std::{fs, io, path::Path};
+
+/// Writes `text` to `path`, creating the save folder first
+pub fn write_file(path: &str, text: impl AsRef<[u8]>) -> io::Result<()> {
+    Path::new(path)
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(path, text))
+}
+
+/// Like `write_file()`, but reports a failure instead of returning it, `what` names the setting
+pub fn save_file(path: &str, what: &str, text: impl AsRef<[u8]>) {
+    if let Err(e) = write_file(path, text) {
+        eprintln!("Could not save {what} to {path}: {e}");
+    }
+}
+
+/// The `<key> <value>` lines of `text`, lines without a value are skipped
+pub fn key_values(text: &str) -> impl Iterator<Item = (&str, &str)> {
+    text.lines()
+        .filter_map(|line| line.trim().split_once(' '))
+        .map(|(key, value)| (key, value.trim()))
+}
+
+/// The `<key> <value>` lines of a settings file, none if it's missing
+pub fn read_key_values(path: &str) -> Vec<(String, String)> {
+    let text = fs::read_to_string(path).unwrap_or_default();
+    key_values(&text)
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::key_values;
+
+    #[test]
+    fn reads_key_values() {
+        let text = "volume 70\n  muted  true \nbroken\n\nname two words\n";
+        assert_eq!(
+            key_values(text).collect::<Vec<_>>(),
+            [("volume", "70"), ("muted", "true"), ("name", "two words")]
+        );
+    }
+}
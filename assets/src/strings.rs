@@ -0,0 +1,127 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{OnceLock, RwLock},
+};
+
+use crate::settings::save_file;
+
+/// One `<language>.txt` per language
+pub const LANG_DIR: &str = "res/lang";
+/// Used for keys missing from other languages
+pub const DEFAULT_LANGUAGE: &str = "en";
+/// Shared by every game
+pub const LANGUAGE_FILE: &str = "save/language.txt";
+
+/// A string table for one language
+pub struct Strings {
+    language: String,
+    table: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Strings {
+    pub fn path(language: &str) -> PathBuf {
+        Path::new(LANG_DIR).join(format!("{language}.txt"))
+    }
+
+    /// Reads `key = text` lines, blank lines and lines starting with '#' are skipped
+    fn read(language: &str) -> HashMap<String, String> {
+        let path = Self::path(language);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("Could not load strings from {}: {e}", path.display());
+                return HashMap::new();
+            }
+        };
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, text)| (key.trim().to_string(), text.trim().to_string()))
+            .collect()
+    }
+
+    pub fn load(language: &str) -> Self {
+        Self {
+            language: language.to_string(),
+            table: Self::read(language),
+            fallback: match language {
+                DEFAULT_LANGUAGE => HashMap::new(),
+                _ => Self::read(DEFAULT_LANGUAGE),
+            },
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Falls back to the default language, then to the key itself so missing strings are visible
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.table
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, |text| text.as_str())
+    }
+}
+
+fn strings() -> &'static RwLock<Strings> {
+    static STRINGS: OnceLock<RwLock<Strings>> = OnceLock::new();
+    STRINGS.get_or_init(|| {
+        let language = fs::read_to_string(LANGUAGE_FILE)
+            .ok()
+            .map(|text| text.trim().to_string())
+            .filter(|language| Strings::path(language).is_file())
+            .unwrap_or(DEFAULT_LANGUAGE.to_string());
+        RwLock::new(Strings::load(&language))
+    })
+}
+
+/// The text for `key` in the current language
+pub fn tr(key: &str) -> String {
+    let strings = strings().read().unwrap_or_else(|e| e.into_inner());
+    strings.get(key).to_string()
+}
+
+/// Like `tr()`, replacing each `{name}` with its value
+pub fn tr_with(key: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(tr(key), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+pub fn language() -> String {
+    let strings = strings().read().unwrap_or_else(|e| e.into_inner());
+    strings.language().to_string()
+}
+
+/// Every language with a file in `LANG_DIR`, sorted
+pub fn languages() -> Vec<String> {
+    let mut languages = fs::read_dir(LANG_DIR)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+                .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    languages.sort();
+    languages
+}
+
+/// Switches and saves the language
+pub fn set_language(language: &str) {
+    *strings().write().unwrap_or_else(|e| e.into_inner()) = Strings::load(language);
+    save_file(LANGUAGE_FILE, "language", language);
+}
+
+/// Rereads the current language, e.g. after its file changed
+pub fn reload() {
+    let mut strings = strings().write().unwrap_or_else(|e| e.into_inner());
+    *strings = Strings::load(&strings.language);
+}
@@ -0,0 +1,7 @@
+mod mixer_sys;
+
+pub mod mixer;
+pub mod settings;
+
+pub use mixer::{Mixer, Music, Sound};
+pub use settings::AudioSettings;
@@ -0,0 +1,163 @@
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_int,
+    path::Path,
+};
+
+use hyperfold_engine::sdl2::{SDL_GetError, SDL_InitSubSystem, SDL_QuitSubSystem, SDL_INIT_AUDIO};
+
+use crate::{mixer_sys::*, settings::AudioSettings};
+
+fn sdl_error() -> String {
+    // SAFETY: SDL always returns a valid, possibly empty, string
+    unsafe { CStr::from_ptr(SDL_GetError()) }
+        .to_string_lossy()
+        .to_string()
+}
+
+/// `fopen` mode for `SDL_RWFromFile`
+const READ_BINARY: &CStr = c"rb";
+
+fn c_path(path: &Path) -> Option<CString> {
+    CString::new(path.to_string_lossy().as_bytes()).ok()
+}
+
+fn open_audio() -> bool {
+    unsafe {
+        if SDL_InitSubSystem(SDL_INIT_AUDIO) != 0 {
+            return false;
+        }
+        if Mix_OpenAudio(MIX_DEFAULT_FREQUENCY, MIX_DEFAULT_FORMAT, 2, 1024) != 0 {
+            SDL_QuitSubSystem(SDL_INIT_AUDIO);
+            return false;
+        }
+    }
+    true
+}
+
+/// A sound effect, must be dropped before the `Mixer` that loaded it
+pub struct Sound(*mut Mix_Chunk);
+
+impl Drop for Sound {
+    fn drop(&mut self) {
+        unsafe { Mix_FreeChunk(self.0) }
+    }
+}
+
+/// A music track, must be dropped before the `Mixer` that loaded it
+pub struct Music(*mut Mix_Music);
+
+impl Drop for Music {
+    fn drop(&mut self) {
+        unsafe { Mix_FreeMusic(self.0) }
+    }
+}
+
+/// Owns the audio device
+/// If it can't be opened every call is a no-op, so games still run without audio
+/// Headless runs can set `SDL_AUDIODRIVER=dummy`
+pub struct Mixer {
+    open: bool,
+    settings: AudioSettings,
+}
+
+impl Mixer {
+    pub fn new(settings: AudioSettings) -> Self {
+        let open = open_audio();
+        if !open {
+            eprintln!(
+                "Could not open audio, continuing without sound: {}",
+                sdl_error()
+            );
+        }
+        let mut mixer = Self { open, settings };
+        mixer.apply_volume();
+        mixer
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Returns None if audio is unavailable or the file can't be loaded
+    pub fn load_sound(&self, path: impl AsRef<Path>) -> Option<Sound> {
+        let path = path.as_ref();
+        let file = c_path(path).filter(|_| self.open)?;
+        // A null file is reported by `Mix_LoadWAV_RW` like any other load error
+        let chunk = unsafe {
+            let src = SDL_RWFromFile(file.as_ptr(), READ_BINARY.as_ptr());
+            Mix_LoadWAV_RW(src, 1)
+        };
+        if chunk.is_null() {
+            eprintln!("Could not load sound {}: {}", path.display(), sdl_error());
+            return None;
+        }
+        Some(Sound(chunk))
+    }
+
+    /// Returns None if audio is unavailable or the file can't be loaded
+    pub fn load_music(&self, path: impl AsRef<Path>) -> Option<Music> {
+        let path = path.as_ref();
+        let file = c_path(path).filter(|_| self.open)?;
+        let music = unsafe { Mix_LoadMUS(file.as_ptr()) };
+        if music.is_null() {
+            eprintln!("Could not load music {}: {}", path.display(), sdl_error());
+            return None;
+        }
+        Some(Music(music))
+    }
+
+    /// Plays on the first free channel, skipped if every channel is busy
+    pub fn play(&self, sound: &Sound) {
+        if self.open {
+            unsafe { Mix_PlayChannelTimed(-1, sound.0, 0, -1) };
+        }
+    }
+
+    /// Loops `music` until other music is played or it is stopped
+    pub fn play_music(&self, music: &Music) {
+        if self.open {
+            unsafe { Mix_PlayMusic(music.0, -1) };
+        }
+    }
+
+    pub fn stop_music(&self) {
+        if self.open {
+            unsafe { Mix_HaltMusic() };
+        }
+    }
+
+    pub fn settings(&self) -> AudioSettings {
+        self.settings
+    }
+
+    /// Applies and saves new settings
+    pub fn set_settings(&mut self, settings: AudioSettings) {
+        self.settings = settings;
+        self.settings.save();
+        self.apply_volume();
+    }
+
+    fn apply_volume(&mut self) {
+        if !self.open {
+            return;
+        }
+        let volume = self.settings.effective_volume() as c_int * MIX_MAX_VOLUME / 100;
+        unsafe {
+            Mix_Volume(-1, volume);
+            Mix_VolumeMusic(volume);
+        }
+    }
+}
+
+impl Drop for Mixer {
+    fn drop(&mut self) {
+        if self.open {
+            unsafe {
+                Mix_HaltMusic();
+                Mix_CloseAudio();
+                SDL_QuitSubSystem(SDL_INIT_AUDIO);
+            }
+        }
+    }
+}
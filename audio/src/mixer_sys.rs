@@ -0,0 +1,52 @@
+//! The subset of SDL_mixer used by `Mixer`, and SDL to open files for it
+#![allow(non_camel_case_types)]
+
+use std::os::raw::{c_char, c_int};
+
+pub const MIX_MAX_VOLUME: c_int = 128;
+/// AUDIO_S16LSB
+pub const MIX_DEFAULT_FORMAT: u16 = 0x8010;
+pub const MIX_DEFAULT_FREQUENCY: c_int = 44100;
+
+#[repr(C)]
+pub struct Mix_Chunk {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct Mix_Music {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct SDL_RWops {
+    _private: [u8; 0],
+}
+
+#[link(name = "SDL2")]
+extern "C" {
+    pub fn SDL_RWFromFile(file: *const c_char, mode: *const c_char) -> *mut SDL_RWops;
+}
+
+#[link(name = "SDL2_mixer")]
+extern "C" {
+    pub fn Mix_OpenAudio(frequency: c_int, format: u16, channels: c_int, chunksize: c_int)
+        -> c_int;
+    pub fn Mix_CloseAudio();
+    /// `Mix_LoadWAV` is only a function from SDL_mixer 2.6, before that it is a macro for this
+    /// Frees `src` if `freesrc` is non-zero, even if loading fails
+    pub fn Mix_LoadWAV_RW(src: *mut SDL_RWops, freesrc: c_int) -> *mut Mix_Chunk;
+    pub fn Mix_FreeChunk(chunk: *mut Mix_Chunk);
+    pub fn Mix_LoadMUS(file: *const c_char) -> *mut Mix_Music;
+    pub fn Mix_FreeMusic(music: *mut Mix_Music);
+    pub fn Mix_PlayChannelTimed(
+        channel: c_int,
+        chunk: *mut Mix_Chunk,
+        loops: c_int,
+        ticks: c_int,
+    ) -> c_int;
+    pub fn Mix_PlayMusic(music: *mut Mix_Music, loops: c_int) -> c_int;
+    pub fn Mix_HaltMusic() -> c_int;
+    pub fn Mix_Volume(channel: c_int, volume: c_int) -> c_int;
+    pub fn Mix_VolumeMusic(volume: c_int) -> c_int;
+}
@@ -0,0 +1,62 @@
+use assets::settings::{read_key_values, save_file};
+
+pub const AUDIO_SETTINGS_FILE: &str = "save/audio_settings.txt";
+pub const VOLUME_STEP: u8 = 10;
+
+/// Shared by every game
+#[derive(Copy, Clone)]
+pub struct AudioSettings {
+    /// Percent of full volume
+    pub volume: u8,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            volume: 70,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// Reads `<setting> <value>` lines
+    /// A missing or malformed file just means default settings
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+        for (key, value) in read_key_values(AUDIO_SETTINGS_FILE) {
+            match key.as_str() {
+                "volume" => {
+                    if let Ok(v) = value.parse::<u8>() {
+                        settings.volume = v.min(100);
+                    }
+                }
+                "muted" => settings.muted = value == "true",
+                _ => (),
+            }
+        }
+        settings
+    }
+
+    pub fn save(&self) {
+        let text = format!("volume {}\nmuted {}\n", self.volume, self.muted);
+        save_file(AUDIO_SETTINGS_FILE, "audio settings", text);
+    }
+
+    pub fn volume_up(&mut self) {
+        self.volume = (self.volume + VOLUME_STEP).min(100);
+    }
+
+    pub fn volume_down(&mut self) {
+        self.volume = self.volume.saturating_sub(VOLUME_STEP);
+    }
+
+    /// Volume to actually play at
+    pub fn effective_volume(&self) -> u8 {
+        match self.muted {
+            true => 0,
+            false => self.volume,
+        }
+    }
+}
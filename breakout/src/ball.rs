@@ -0,0 +1,164 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::{
+        event_system::events::Key,
+        physics::{BoundaryCollision, PhysicsData, Position},
+        render_system::{
+            render_data::RenderTexture, Elevation, RenderComponent, Renderer, Texture,
+        },
+    },
+    utils::{
+        colors::WHITE,
+        rect::{Align, PointF, Rect},
+    },
+};
+
+use crate::{
+    _engine::{Components, Events},
+    board_rect,
+    controls::{BreakoutAction, ControlsCS},
+    elevations::Elevations,
+    lives::LoseLife,
+    paddle::{paddle_start, PaddlePos, PADDLE_H, PADDLE_W},
+    Playing,
+};
+
+pub const BALL_W: f32 = 12.0;
+pub const BALL_SPEED: f32 = 300.0;
+pub const MAX_BALL_SPEED: f32 = 600.0;
+/// Maximum angle from vertical when bouncing off the edge of the paddle
+pub const MAX_BOUNCE_ANGLE: f32 = 60.0;
+
+/// Returns the velocity for a ball leaving the paddle
+/// `offset` is in [-1, 1] with -1 being the left edge of the paddle
+pub fn bounce_velocity(offset: f32, speed: f32) -> PointF {
+    let angle = (offset.clamp(-1.0, 1.0) * MAX_BOUNCE_ANGLE).to_radians();
+    PointF {
+        x: speed * angle.sin(),
+        y: -speed * angle.cos(),
+    }
+}
+
+#[hyperfold_engine::component(Singleton)]
+struct Ball {
+    pub launched: bool,
+    pub speed: f32,
+}
+
+#[hyperfold_engine::event]
+struct ResetBall;
+
+#[hyperfold_engine::system]
+fn new_ball(_: &Playing::OnEnter, entities: &mut dyn Components, r: &Renderer) {
+    let pos = paddle_start();
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Ball {
+            launched: false,
+            speed: BALL_SPEED
+        },
+        Playing::Label,
+        Elevation(Elevations::Ball as u8),
+        RenderComponent::new(RenderTexture::new(Some(Texture::new(
+            r,
+            BALL_W as u32,
+            BALL_W as u32,
+            WHITE
+        )))),
+        Position(Rect::from(
+            pos.x,
+            pos.y - PADDLE_H / 2.0,
+            BALL_W,
+            BALL_W,
+            Align::Center,
+            Align::BotRight
+        )),
+        PhysicsData {
+            v: PointF::new(),
+            a: PointF::new(),
+            boundary: Some(board_rect())
+        }
+    );
+}
+
+components!(
+    labels(Ball),
+    BallPhysics,
+    ball: &'a mut Ball,
+    pos: &'a mut Position,
+    physics: &'a mut PhysicsData
+);
+
+#[hyperfold_engine::system]
+fn reset_ball(_: &ResetBall, ball: BallPhysics) {
+    ball.ball.launched = false;
+    ball.ball.speed = BALL_SPEED;
+    ball.physics.v = PointF::new();
+}
+
+#[hyperfold_engine::system]
+fn launch_ball(key: &Key, ball: BallPhysics, ControlsCS { controls, .. }: ControlsCS) {
+    if !ball.ball.launched
+        && key.0.pressed()
+        && controls.action(key.0.key) == Some(BreakoutAction::Launch)
+    {
+        ball.ball.launched = true;
+        ball.physics.v = bounce_velocity(0.2, ball.ball.speed);
+    }
+}
+
+#[hyperfold_engine::system]
+fn follow_paddle(_: &Update, ball: BallPhysics, paddle: PaddlePos) {
+    if !ball.ball.launched {
+        ball.pos.0.set_pos(
+            paddle.pos.0.cx(),
+            paddle.pos.0.y,
+            Align::Center,
+            Align::BotRight,
+        );
+    }
+}
+
+#[hyperfold_engine::system]
+fn bounce_paddle(_: &Update, ball: BallPhysics, paddle: PaddlePos) {
+    // Only bounce balls moving down so we don't get stuck inside the paddle
+    if !ball.ball.launched || ball.physics.v.y <= 0.0 || !ball.pos.0.intersects(&paddle.pos.0) {
+        return;
+    }
+
+    let offset = (ball.pos.0.cx() - paddle.pos.0.cx()) / (PADDLE_W / 2.0);
+    ball.physics.v = bounce_velocity(offset, ball.ball.speed);
+    ball.pos.0.set_pos(
+        ball.pos.0.cx(),
+        paddle.pos.0.y,
+        Align::Center,
+        Align::BotRight,
+    );
+}
+
+#[hyperfold_engine::system]
+fn bounce_wall(collide: &BoundaryCollision, ball: BallPhysics, events: &mut dyn Events) {
+    if collide.0 != *ball.eid {
+        return;
+    }
+
+    let bounds = board_rect();
+    let pos = &ball.pos.0;
+    let v = &mut ball.physics.v;
+    if pos.y + pos.h >= bounds.y + bounds.h {
+        events.new_event(LoseLife);
+        return;
+    }
+    if pos.x <= bounds.x {
+        v.x = v.x.abs();
+    } else if pos.x + pos.w >= bounds.x + bounds.w {
+        v.x = -v.x.abs();
+    }
+    if pos.y <= bounds.y {
+        v.y = v.y.abs();
+    }
+}
@@ -0,0 +1,245 @@
+use std::{fs, path::PathBuf};
+
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{
+        entities::{EntityTrash, NewEntity},
+        events::core::Update,
+    },
+    framework::{
+        physics::Position,
+        render_system::{
+            drawable::Canvas,
+            render_data::RenderTexture,
+            shapes::{Rectangle, ShapeTrait},
+            Elevation, RenderComponent, Renderer, Texture,
+        },
+    },
+    sdl2::SDL_Color,
+    utils::{
+        colors::gray,
+        rect::{Align, Rect},
+    },
+};
+
+use crate::{
+    _engine::{Components, Events},
+    ball::{BallPhysics, ResetBall, MAX_BALL_SPEED},
+    elevations::Elevations,
+    Playing, BOARD_H, BOARD_W,
+};
+
+pub const LEVEL_DIR: &str = "res/breakout/levels";
+pub const BRICK_H: f32 = 20.0;
+/// Empty space between the top of the board and the first row of bricks
+pub const BRICK_TOP: f32 = 3.0 * BRICK_H;
+/// Speed gained by the ball each time it hits a brick
+pub const BRICK_SPEEDUP: f32 = 4.0;
+
+const BRICK_COLORS: [SDL_Color; 4] = [
+    SDL_Color {
+        r: 80,
+        g: 200,
+        b: 120,
+        a: 255,
+    },
+    SDL_Color {
+        r: 240,
+        g: 200,
+        b: 60,
+        a: 255,
+    },
+    SDL_Color {
+        r: 240,
+        g: 120,
+        b: 40,
+        a: 255,
+    },
+    SDL_Color {
+        r: 220,
+        g: 50,
+        b: 50,
+        a: 255,
+    },
+];
+
+/// Returns every level file, sorted by name
+pub fn level_files() -> Vec<PathBuf> {
+    let mut files = fs::read_dir(LEVEL_DIR)
+        .unwrap_or_else(|e| panic!("Could not read {LEVEL_DIR}: {e}"))
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+/// Parses a level layout
+/// Each line is a row of bricks, digits give the number of hits
+/// and any other character leaves a gap. Lines starting with '#' are comments
+pub fn parse_level(text: &str) -> Vec<Vec<u8>> {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| {
+            line.trim_end()
+                .chars()
+                .map(|c| c.to_digit(10).unwrap_or(0) as u8)
+                .collect()
+        })
+        .collect()
+}
+
+/// Loads level `level`, wrapping around once all levels are cleared
+pub fn load_level(level: usize) -> Vec<Vec<u8>> {
+    let files = level_files();
+    if files.is_empty() {
+        panic!("No levels found in {LEVEL_DIR}");
+    }
+    let file = &files[level % files.len()];
+    parse_level(
+        &fs::read_to_string(file)
+            .unwrap_or_else(|e| panic!("Could not read {}: {e}", file.display())),
+    )
+}
+
+fn brick_texture(hits: u8, w: f32, r: &Renderer) -> RenderComponent {
+    let color = BRICK_COLORS[(hits.max(1) as usize - 1).min(BRICK_COLORS.len() - 1)];
+    let tex = Texture::new(r, w as u32, BRICK_H as u32, color);
+    tex.draw(
+        r,
+        &mut Rectangle::new().set_color(gray(40)).border(
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                w,
+                h: BRICK_H,
+            },
+            -1.0,
+            false,
+        ),
+    );
+    RenderComponent::new(RenderTexture::new(Some(tex)))
+}
+
+#[hyperfold_engine::component]
+struct Brick {
+    pub hits: u8,
+}
+
+#[hyperfold_engine::component(Singleton)]
+struct Level(pub usize);
+
+#[hyperfold_engine::event]
+struct LoadLevel;
+
+#[hyperfold_engine::system]
+fn new_level(_: &Playing::OnEnter, entities: &mut dyn Components, events: &mut dyn Events) {
+    let e = Entity::new();
+    add_components!(entities, e, Level(0), Playing::Label);
+
+    events.new_event(LoadLevel);
+}
+
+components!(LevelCS, level: &'a mut Level);
+
+#[hyperfold_engine::system]
+fn new_bricks(
+    _: &LoadLevel,
+    LevelCS { level, .. }: LevelCS,
+    entities: &mut dyn Components,
+    r: &Renderer,
+) {
+    let rows = load_level(level.0);
+    for (y, row) in rows.iter().enumerate() {
+        let w = BOARD_W / row.len().max(1) as f32;
+        for (x, hits) in row.iter().enumerate().filter(|(_, hits)| **hits > 0) {
+            let e = Entity::new();
+            add_components!(
+                entities,
+                e,
+                Brick { hits: *hits },
+                Playing::Label,
+                Elevation(Elevations::Bricks as u8),
+                brick_texture(*hits, w, r),
+                Position(Rect::from(
+                    -BOARD_W / 2.0 + x as f32 * w,
+                    -BOARD_H / 2.0 + BRICK_TOP + y as f32 * BRICK_H,
+                    w,
+                    BRICK_H,
+                    Align::TopLeft,
+                    Align::TopLeft
+                ))
+            );
+        }
+    }
+}
+
+components!(
+    Bricks,
+    brick: &'a mut Brick,
+    pos: &'a Position,
+    tex: &'a mut RenderComponent
+);
+
+#[hyperfold_engine::system]
+fn collide_bricks(
+    _: &Update,
+    mut bricks: Vec<Bricks>,
+    ball: BallPhysics,
+    LevelCS { level, .. }: LevelCS,
+    trash: &mut EntityTrash,
+    events: &mut dyn Events,
+    r: &Renderer,
+) {
+    if !ball.ball.launched {
+        return;
+    }
+
+    // Only hit one brick per frame so two bricks don't cancel each other's bounce
+    let b = &ball.pos.0;
+    let Some(brick) = bricks
+        .iter_mut()
+        .find(|brick| brick.brick.hits > 0 && brick.pos.0.intersects(b))
+    else {
+        return;
+    };
+
+    // Bounce along the axis with the smallest overlap
+    let p = &brick.pos.0;
+    let overlap_x = (b.x + b.w).min(p.x + p.w) - b.x.max(p.x);
+    let overlap_y = (b.y + b.h).min(p.y + p.h) - b.y.max(p.y);
+    let v = &mut ball.physics.v;
+    if overlap_x < overlap_y {
+        v.x = if b.cx() < p.cx() {
+            -v.x.abs()
+        } else {
+            v.x.abs()
+        };
+    } else {
+        v.y = if b.cy() < p.cy() {
+            -v.y.abs()
+        } else {
+            v.y.abs()
+        };
+    }
+
+    let speed = (ball.ball.speed + BRICK_SPEEDUP).min(MAX_BALL_SPEED);
+    let scale = speed / ball.ball.speed;
+    v.x *= scale;
+    v.y *= scale;
+    ball.ball.speed = speed;
+
+    brick.brick.hits -= 1;
+    match brick.brick.hits {
+        0 => trash.0.push(*brick.eid),
+        hits => *brick.tex = brick_texture(hits, p.w, r),
+    }
+
+    // Level cleared
+    if bricks.iter().all(|brick| brick.brick.hits == 0) {
+        level.0 += 1;
+        events.new_event(LoadLevel);
+        events.new_event(ResetBall);
+    }
+}
@@ -0,0 +1,81 @@
+use hyperfold_engine::{
+    _engine::Entity, add_components, components, ecs::entities::NewEntity, sdl2::SDL_KeyCode,
+};
+
+use input::{Action, Binding, Bindings};
+
+use crate::{_engine::Components, Playing};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum BreakoutAction {
+    MoveLeft,
+    MoveRight,
+    Launch,
+    Restart,
+}
+
+impl Action for BreakoutAction {
+    const GAME: &'static str = "breakout";
+    const ALL: &'static [Self] = &[
+        BreakoutAction::MoveLeft,
+        BreakoutAction::MoveRight,
+        BreakoutAction::Launch,
+        BreakoutAction::Restart,
+    ];
+
+    fn id(&self) -> &'static str {
+        match self {
+            BreakoutAction::MoveLeft => "move_left",
+            BreakoutAction::MoveRight => "move_right",
+            BreakoutAction::Launch => "launch",
+            BreakoutAction::Restart => "restart",
+        }
+    }
+
+    fn defaults(&self) -> Vec<Binding> {
+        let keys: &[SDL_KeyCode] = match self {
+            BreakoutAction::MoveLeft => &[SDL_KeyCode::SDLK_a, SDL_KeyCode::SDLK_LEFT],
+            BreakoutAction::MoveRight => &[SDL_KeyCode::SDLK_d, SDL_KeyCode::SDLK_RIGHT],
+            BreakoutAction::Launch => &[SDL_KeyCode::SDLK_SPACE],
+            BreakoutAction::Restart => &[SDL_KeyCode::SDLK_r],
+        };
+        keys.iter().map(|key| Binding::Key(*key as i32)).collect()
+    }
+}
+
+/// Bindings from the `[breakout]` section of the controls file
+#[hyperfold_engine::component(Singleton)]
+struct Controls {
+    pub bindings: Bindings<BreakoutAction>,
+    /// Set once this game starts, the games launch from the same binary and share key events
+    pub active: bool,
+}
+
+impl Controls {
+    /// The action an SDL key triggers, none before this game starts
+    pub fn action(&self, key: SDL_KeyCode) -> Option<BreakoutAction> {
+        self.bindings
+            .action(Binding::Key(key as i32))
+            .filter(|_| self.active)
+    }
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_controls(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Controls {
+            bindings: Bindings::load(),
+            active: false
+        }
+    );
+}
+
+components!(ControlsCS, controls: &'a mut Controls);
+
+#[hyperfold_engine::system]
+fn activate_controls(_: &Playing::OnEnter, ControlsCS { controls, .. }: ControlsCS) {
+    controls.active = true;
+}
@@ -0,0 +1,10 @@
+#[repr(u8)]
+pub enum Elevations {
+    Background = 0,
+    Bricks,
+    Paddle,
+    Ball,
+    Lives,
+    GameOverScreen,
+    GameOverText,
+}
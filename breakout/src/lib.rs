@@ -0,0 +1,152 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::entities::NewEntity,
+    framework::{
+        event_system::events::Key,
+        physics::Position,
+        render_system::{
+            drawable::Canvas,
+            font::TIMES,
+            render_data::RenderTexture,
+            shapes::{Rectangle, ShapeTrait},
+            AssetManager, Elevation, RenderComponent, Renderer, Texture,
+        },
+    },
+    sdl2::SDL_Color,
+    utils::{
+        colors::gray,
+        rect::{Align, Rect},
+    },
+};
+
+use assets::strings::{tr, tr_with};
+use ui::{GameOverText, OverlayLine};
+
+use crate::{
+    controls::{BreakoutAction, ControlsCS},
+    elevations::Elevations,
+};
+
+pub mod ball;
+pub mod bricks;
+pub mod controls;
+pub mod elevations;
+pub mod lives;
+pub mod paddle;
+
+use hyperfold_engine::system_macro;
+
+hyperfold_engine::game_crate!();
+
+pub const BOARD_W_I: u32 = 500;
+pub const BOARD_H_I: u32 = 600;
+
+pub const BOARD_W: f32 = BOARD_W_I as f32;
+pub const BOARD_H: f32 = BOARD_H_I as f32;
+
+pub fn board_rect() -> Rect {
+    Rect::from(0.0, 0.0, BOARD_W, BOARD_H, Align::Center, Align::Center)
+}
+
+#[hyperfold_engine::state]
+struct Playing;
+
+#[hyperfold_engine::state]
+struct GameOver;
+
+#[hyperfold_engine::component(Singleton)]
+struct Background;
+
+components!(labels(Background), BackgroundEids);
+
+#[hyperfold_engine::system]
+fn create_bkgrnd(
+    _: &Playing::OnEnter,
+    bkgrnd: Vec<BackgroundEids>,
+    entities: &mut dyn _engine::Components,
+    r: &Renderer,
+) {
+    if !bkgrnd.is_empty() {
+        return;
+    }
+
+    let tex = Texture::new(r, BOARD_W_I, BOARD_H_I, gray(40));
+    tex.draw(
+        r,
+        &mut Rectangle::new().set_color(gray(120)).border(
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                w: BOARD_W,
+                h: BOARD_H,
+            },
+            -2.0,
+            false,
+        ),
+    );
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Background,
+        Elevation(Elevations::Background as u8),
+        RenderComponent::new(RenderTexture::new(Some(tex))),
+        Position(board_rect())
+    );
+}
+
+#[hyperfold_engine::component(Singleton)]
+struct GameOverScreen;
+
+components!(labels(GameOverScreen), GameOverEids);
+
+#[hyperfold_engine::system]
+fn game_over(
+    _: &GameOver::OnEnter,
+    ControlsCS { controls, .. }: ControlsCS,
+    entities: &mut dyn _engine::Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let title = tr("game_over");
+    let hint = tr_with(
+        "breakout_restart_hint",
+        &[("restart", &controls.bindings.hint(BreakoutAction::Restart))],
+    );
+    let tex = GameOverText {
+        title: OverlayLine::new(&title, BOARD_W_I / 3),
+        lines: vec![OverlayLine::new(&hint, BOARD_W_I / 2)],
+        font: TIMES.to_string(),
+        overlay: SDL_Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 64,
+        },
+    }
+    .texture(BOARD_W_I, BOARD_H_I, r, am);
+
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        GameOverScreen,
+        GameOver::Label,
+        Elevation(Elevations::GameOverScreen as u8),
+        RenderComponent::new(RenderTexture::new(Some(tex))),
+        Position(board_rect())
+    );
+}
+
+#[hyperfold_engine::system]
+fn restart(
+    key: &Key,
+    game_over: Vec<GameOverEids>,
+    ControlsCS { controls, .. }: ControlsCS,
+    events: &mut dyn _engine::Events,
+) {
+    if !game_over.is_empty() && controls.action(key.0.key) == Some(BreakoutAction::Restart) {
+        events.set_state(Playing::Data);
+    }
+}
@@ -0,0 +1,90 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::entities::{EntityTrash, NewEntity},
+    framework::{
+        physics::Position,
+        render_system::{
+            render_data::RenderTexture, Elevation, RenderComponent, Renderer, Texture,
+        },
+    },
+    utils::{
+        colors::WHITE,
+        rect::{Align, Rect},
+    },
+};
+
+use crate::{
+    _engine::{Components, Events},
+    ball::{ResetBall, BALL_W},
+    elevations::Elevations,
+    GameOver, Playing, BOARD_H, BOARD_W,
+};
+
+pub const START_LIVES: u8 = 3;
+
+#[hyperfold_engine::component(Singleton)]
+struct Lives(pub u8);
+
+/// One icon is shown in the top left for each remaining life
+#[hyperfold_engine::component]
+struct LifeIcon(pub u8);
+
+#[hyperfold_engine::event]
+struct LoseLife;
+
+#[hyperfold_engine::system]
+fn new_lives(_: &Playing::OnEnter, entities: &mut dyn Components, r: &Renderer) {
+    let e = Entity::new();
+    add_components!(entities, e, Lives(START_LIVES), Playing::Label);
+
+    for i in 0..START_LIVES {
+        let e = Entity::new();
+        add_components!(
+            entities,
+            e,
+            LifeIcon(i),
+            Playing::Label,
+            Elevation(Elevations::Lives as u8),
+            RenderComponent::new(RenderTexture::new(Some(Texture::new(
+                r,
+                BALL_W as u32,
+                BALL_W as u32,
+                WHITE
+            )))),
+            Position(Rect::from(
+                -BOARD_W / 2.0 + BALL_W * (2 * i + 1) as f32,
+                -BOARD_H / 2.0 + BALL_W,
+                BALL_W,
+                BALL_W,
+                Align::TopLeft,
+                Align::TopLeft
+            ))
+        );
+    }
+}
+
+components!(LivesCS, lives: &'a mut Lives);
+
+components!(IconsCS, icon: &'a LifeIcon);
+
+#[hyperfold_engine::system]
+fn lose_life(
+    _: &LoseLife,
+    LivesCS { lives, .. }: LivesCS,
+    icons: Vec<IconsCS>,
+    trash: &mut EntityTrash,
+    events: &mut dyn Events,
+) {
+    lives.0 = lives.0.saturating_sub(1);
+    trash.0.extend(
+        icons
+            .into_iter()
+            .filter_map(|icon| (icon.icon.0 >= lives.0).then_some(*icon.eid)),
+    );
+
+    match lives.0 {
+        0 => events.set_state(GameOver::Data),
+        _ => events.new_event(ResetBall),
+    }
+}
@@ -0,0 +1,95 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::entities::NewEntity,
+    framework::{
+        event_system::events::Key,
+        physics::{PhysicsData, Position},
+        render_system::{
+            render_data::RenderTexture, Camera, Elevation, RenderComponent, Renderer, Texture,
+        },
+    },
+    sdl2::SDL_Color,
+    utils::rect::{Align, PointF, Rect},
+};
+
+use crate::{
+    _engine::Components,
+    board_rect,
+    controls::{BreakoutAction, ControlsCS},
+    elevations::Elevations,
+    Playing, BOARD_H,
+};
+
+pub const PADDLE_W: f32 = 80.0;
+pub const PADDLE_H: f32 = 12.0;
+pub const PADDLE_SPEED: f32 = 350.0;
+
+const PADDLE_COLOR: SDL_Color = SDL_Color {
+    r: 64,
+    g: 160,
+    b: 255,
+    a: 255,
+};
+
+pub fn paddle_start() -> PointF {
+    PointF {
+        x: 0.0,
+        y: BOARD_H / 2.0 - 3.0 * PADDLE_H,
+    }
+}
+
+#[hyperfold_engine::component(Singleton)]
+struct Paddle;
+
+#[hyperfold_engine::system]
+fn new_paddle(
+    _: &Playing::OnEnter,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    camera: &mut Camera,
+) {
+    camera.0.set_pos(0.0, 0.0, Align::Center, Align::Center);
+
+    let pos = paddle_start();
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Paddle,
+        Playing::Label,
+        Elevation(Elevations::Paddle as u8),
+        RenderComponent::new(RenderTexture::new(Some(Texture::new(
+            r,
+            PADDLE_W as u32,
+            PADDLE_H as u32,
+            PADDLE_COLOR
+        )))),
+        Position(Rect::from_center(pos.x, pos.y, PADDLE_W, PADDLE_H)),
+        PhysicsData {
+            v: PointF::new(),
+            a: PointF::new(),
+            boundary: Some(board_rect())
+        }
+    );
+}
+
+components!(labels(Paddle), PaddlePos, pos: &'a Position);
+
+components!(labels(Paddle), PaddlePhysics, physics: &'a mut PhysicsData);
+
+#[hyperfold_engine::system]
+fn move_paddle(key: &Key, paddle: PaddlePhysics, ControlsCS { controls, .. }: ControlsCS) {
+    let dir = match controls.action(key.0.key) {
+        Some(BreakoutAction::MoveLeft) => -1.0,
+        Some(BreakoutAction::MoveRight) => 1.0,
+        _ => return,
+    };
+
+    if key.0.pressed() {
+        paddle.physics.v.x = dir * PADDLE_SPEED;
+    } else if key.0.released() && paddle.physics.v.x * dir > 0.0 {
+        // Only stop if we are still moving in the released direction
+        paddle.physics.v.x = 0.0;
+    }
+}
@@ -0,0 +1,319 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub const WIN_VALUE: u32 = 2048;
+/// Chance out of 10 that a spawned tile is a 4 instead of a 2
+pub const FOUR_CHANCE: u32 = 1;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Tile {
+    /// Unique per tile so the renderer can follow it between moves
+    pub id: u32,
+    pub value: u32,
+}
+
+pub type Cell = (usize, usize);
+
+/// What happened to each tile during a move, used to drive animations
+#[derive(Copy, Clone)]
+pub enum TileEvent {
+    Slide {
+        tile: Tile,
+        from: Cell,
+        to: Cell,
+    },
+    /// Two tiles of half the value slid from `from` into `to` and became `tile`
+    Merge {
+        tile: Tile,
+        from: [Cell; 2],
+        to: Cell,
+    },
+    Spawn {
+        tile: Tile,
+        at: Cell,
+    },
+}
+
+/// Pure 2048 rules, independent of rendering and input
+/// Cloning a board also clones its rng, so undoing a move replays the same spawns
+#[derive(Clone)]
+pub struct Board {
+    pub size: usize,
+    pub score: u32,
+    cells: Vec<Option<Tile>>,
+    next_id: u32,
+    rng: StdRng,
+}
+
+impl Board {
+    pub fn new(size: usize, seed: u64) -> Self {
+        let mut board = Self {
+            size,
+            score: 0,
+            cells: vec![None; size * size],
+            next_id: 0,
+            rng: StdRng::seed_from_u64(seed),
+        };
+        board.spawn();
+        board.spawn();
+        board
+    }
+
+    pub fn get(&self, (x, y): Cell) -> Option<Tile> {
+        self.cells[y * self.size + x]
+    }
+
+    fn set(&mut self, (x, y): Cell, tile: Option<Tile>) {
+        self.cells[y * self.size + x] = tile;
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = (Cell, Tile)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, tile)| tile.map(|tile| ((i % self.size, i / self.size), tile)))
+    }
+
+    pub fn max_value(&self) -> u32 {
+        self.tiles().map(|(_, tile)| tile.value).max().unwrap_or(0)
+    }
+
+    fn new_tile(&mut self, value: u32) -> Tile {
+        self.next_id += 1;
+        Tile {
+            id: self.next_id,
+            value,
+        }
+    }
+
+    /// Places a 2 or 4 in a random empty cell
+    pub fn spawn(&mut self) -> Option<TileEvent> {
+        let empty = (0..self.cells.len())
+            .filter(|i| self.cells[*i].is_none())
+            .collect::<Vec<_>>();
+        if empty.is_empty() {
+            return None;
+        }
+        let i = empty[self.rng.gen_range(0..empty.len())];
+        let value = if self.rng.gen_range(0..10) < FOUR_CHANCE {
+            4
+        } else {
+            2
+        };
+        let tile = self.new_tile(value);
+        self.cells[i] = Some(tile);
+        Some(TileEvent::Spawn {
+            tile,
+            at: (i % self.size, i / self.size),
+        })
+    }
+
+    /// Returns the cells of line `i` ordered from the edge tiles slide towards
+    fn line(&self, dir: Dir, i: usize) -> Vec<Cell> {
+        let n = self.size;
+        (0..n)
+            .map(|j| match dir {
+                Dir::Left => (j, i),
+                Dir::Right => (n - 1 - j, i),
+                Dir::Up => (i, j),
+                Dir::Down => (i, n - 1 - j),
+            })
+            .collect()
+    }
+
+    /// Slides every tile towards `dir`, merging equal pairs once per move
+    /// Returns None if nothing moved, in which case no tile is spawned
+    pub fn shift(&mut self, dir: Dir) -> Option<Vec<TileEvent>> {
+        let mut events = Vec::new();
+        let mut moved = false;
+        for i in 0..self.size {
+            let line = self.line(dir, i);
+            let tiles = line
+                .iter()
+                .filter_map(|cell| self.get(*cell).map(|tile| (*cell, tile)))
+                .collect::<Vec<_>>();
+            line.iter().for_each(|cell| self.set(*cell, None));
+
+            // (from, tile, merged) for each tile after sliding
+            let mut out: Vec<(Cell, Tile, bool)> = Vec::new();
+            for (from, tile) in tiles {
+                let to = line[out.len().max(1) - 1];
+                match out.last_mut() {
+                    Some((prev_from, prev, merged)) if !*merged && prev.value == tile.value => {
+                        let value = prev.value * 2;
+                        self.next_id += 1;
+                        *prev = Tile {
+                            id: self.next_id,
+                            value,
+                        };
+                        *merged = true;
+                        self.score += value;
+                        events.push(TileEvent::Merge {
+                            tile: *prev,
+                            from: [*prev_from, from],
+                            to,
+                        });
+                        moved = true;
+                    }
+                    _ => out.push((from, tile, false)),
+                }
+            }
+
+            for (to, (from, tile, merged)) in line.iter().zip(out) {
+                self.set(*to, Some(tile));
+                if !merged {
+                    moved |= from != *to;
+                    events.push(TileEvent::Slide {
+                        tile,
+                        from,
+                        to: *to,
+                    });
+                }
+            }
+        }
+
+        if !moved {
+            return None;
+        }
+        events.extend(self.spawn());
+        Some(events)
+    }
+
+    pub fn can_move(&self) -> bool {
+        let n = self.size;
+        let value = |cell| self.get(cell).map(|tile| tile.value);
+        (0..n).any(|y| {
+            (0..n).any(|x| match value((x, y)) {
+                None => true,
+                v => (x + 1 < n && value((x + 1, y)) == v) || (y + 1 < n && value((x, y + 1)) == v),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A board holding `rows`, 0 is an empty cell
+    fn board(rows: &[&[u32]]) -> Board {
+        let mut board = Board::new(rows.len(), 0);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, value) in row.iter().enumerate() {
+                let tile = (*value > 0).then(|| board.new_tile(*value));
+                board.set((x, y), tile);
+            }
+        }
+        board
+    }
+
+    fn rows(board: &Board) -> Vec<Vec<u32>> {
+        (0..board.size)
+            .map(|y| {
+                (0..board.size)
+                    .map(|x| board.get((x, y)).map_or(0, |tile| tile.value))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Shifts and returns the board without the tile spawned after the move
+    fn shift(board: &mut Board, dir: Dir) -> Vec<Vec<u32>> {
+        let events = board.shift(dir).expect("Tiles should move");
+        let mut rows = rows(board);
+        match events.last() {
+            Some(TileEvent::Spawn { at: (x, y), .. }) => rows[*y][*x] = 0,
+            _ => panic!("A tile should spawn after a move"),
+        }
+        rows
+    }
+
+    fn merges(events: &[TileEvent]) -> usize {
+        events
+            .iter()
+            .filter(|event| matches!(event, TileEvent::Merge { .. }))
+            .count()
+    }
+
+    #[test]
+    fn merges_each_tile_once() {
+        let mut b = board(&[&[2, 2, 2, 2], &[0; 4], &[0; 4], &[0; 4]]);
+        assert_eq!(shift(&mut b, Dir::Left)[0], [4, 4, 0, 0]);
+        assert_eq!(b.score, 8);
+
+        // The merged 4 doesn't merge again with the 4 already there
+        let mut b = board(&[&[4, 2, 2, 0], &[0; 4], &[0; 4], &[0; 4]]);
+        assert_eq!(shift(&mut b, Dir::Left)[0], [4, 4, 0, 0]);
+        assert_eq!(b.score, 4);
+
+        let mut b = board(&[&[4, 4, 8, 0], &[0; 4], &[0; 4], &[0; 4]]);
+        assert_eq!(shift(&mut b, Dir::Left)[0], [8, 8, 0, 0]);
+    }
+
+    #[test]
+    fn merges_from_the_edge_moved_towards() {
+        let row = || board(&[&[2, 2, 2, 0], &[0; 4], &[0; 4], &[0; 4]]);
+        assert_eq!(shift(&mut row(), Dir::Left)[0], [4, 2, 0, 0]);
+        assert_eq!(shift(&mut row(), Dir::Right)[0], [0, 0, 2, 4]);
+
+        let column = || board(&[&[2, 0, 0], &[2, 0, 0], &[2, 0, 0]]);
+        let up = shift(&mut column(), Dir::Up);
+        assert_eq!([up[0][0], up[1][0], up[2][0]], [4, 2, 0]);
+        let down = shift(&mut column(), Dir::Down);
+        assert_eq!([down[0][0], down[1][0], down[2][0]], [0, 2, 4]);
+    }
+
+    #[test]
+    fn reports_merges_and_slides() {
+        let mut b = board(&[&[0, 2, 0, 2], &[0, 0, 0, 8], &[0; 4], &[0; 4]]);
+        let events = b.shift(Dir::Left).unwrap();
+        assert_eq!(merges(&events), 1);
+        let merge = events.iter().find_map(|event| match event {
+            TileEvent::Merge { tile, from, to } => Some((tile.value, *from, *to)),
+            _ => None,
+        });
+        assert_eq!(merge, Some((4, [(1, 0), (3, 0)], (0, 0))));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TileEvent::Slide {
+                from: (3, 1),
+                to: (0, 1),
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn no_move_changes_nothing() {
+        let full = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]];
+        let mut b = board(&full.iter().map(|row| &row[..]).collect::<Vec<_>>());
+        assert!(!b.can_move());
+        for dir in [Dir::Up, Dir::Down, Dir::Left, Dir::Right] {
+            assert!(b.shift(dir).is_none());
+            assert_eq!(rows(&b), full);
+            assert_eq!(b.score, 0);
+        }
+
+        // Already against the edge, nothing slides or spawns
+        let mut b = board(&[&[2, 4, 0, 0], &[8, 0, 0, 0], &[0; 4], &[0; 4]]);
+        assert!(b.shift(Dir::Left).is_none());
+        assert!(b.shift(Dir::Up).is_none());
+        assert_eq!(b.tiles().count(), 3);
+        assert!(b.can_move());
+    }
+
+    #[test]
+    fn can_move_with_a_matching_neighbour() {
+        let vertical = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 8], [4, 2, 4, 8]];
+        assert!(board(&vertical.iter().map(|row| &row[..]).collect::<Vec<_>>()).can_move());
+        let horizontal = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 8, 8]];
+        assert!(board(&horizontal.iter().map(|row| &row[..]).collect::<Vec<_>>()).can_move());
+    }
+}
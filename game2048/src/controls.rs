@@ -0,0 +1,86 @@
+use hyperfold_engine::{
+    _engine::Entity, add_components, components, ecs::entities::NewEntity, sdl2::SDL_KeyCode,
+};
+
+use ::input::{Action, Binding, Bindings};
+
+use crate::{_engine::Components, board::Dir, Playing};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Game2048Action {
+    Move(Dir),
+    Undo,
+    Restart,
+}
+
+impl Action for Game2048Action {
+    const GAME: &'static str = "2048";
+    const ALL: &'static [Self] = &[
+        Game2048Action::Move(Dir::Up),
+        Game2048Action::Move(Dir::Down),
+        Game2048Action::Move(Dir::Left),
+        Game2048Action::Move(Dir::Right),
+        Game2048Action::Undo,
+        Game2048Action::Restart,
+    ];
+
+    fn id(&self) -> &'static str {
+        match self {
+            Game2048Action::Move(Dir::Up) => "move_up",
+            Game2048Action::Move(Dir::Down) => "move_down",
+            Game2048Action::Move(Dir::Left) => "move_left",
+            Game2048Action::Move(Dir::Right) => "move_right",
+            Game2048Action::Undo => "undo",
+            Game2048Action::Restart => "restart",
+        }
+    }
+
+    fn defaults(&self) -> Vec<Binding> {
+        let keys: &[SDL_KeyCode] = match self {
+            Game2048Action::Move(Dir::Up) => &[SDL_KeyCode::SDLK_w, SDL_KeyCode::SDLK_UP],
+            Game2048Action::Move(Dir::Down) => &[SDL_KeyCode::SDLK_s, SDL_KeyCode::SDLK_DOWN],
+            Game2048Action::Move(Dir::Left) => &[SDL_KeyCode::SDLK_a, SDL_KeyCode::SDLK_LEFT],
+            Game2048Action::Move(Dir::Right) => &[SDL_KeyCode::SDLK_d, SDL_KeyCode::SDLK_RIGHT],
+            Game2048Action::Undo => &[SDL_KeyCode::SDLK_u],
+            Game2048Action::Restart => &[SDL_KeyCode::SDLK_r],
+        };
+        keys.iter().map(|key| Binding::Key(*key as i32)).collect()
+    }
+}
+
+/// Bindings from the `[2048]` section of the controls file
+#[hyperfold_engine::component(Singleton)]
+struct Controls {
+    pub bindings: Bindings<Game2048Action>,
+    /// Set once this game starts, the games launch from the same binary and share key events
+    pub active: bool,
+}
+
+impl Controls {
+    /// The action an SDL key triggers, none before this game starts
+    pub fn action(&self, key: SDL_KeyCode) -> Option<Game2048Action> {
+        self.bindings
+            .action(Binding::Key(key as i32))
+            .filter(|_| self.active)
+    }
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_controls(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Controls {
+            bindings: Bindings::load(),
+            active: false
+        }
+    );
+}
+
+components!(ControlsCS, controls: &'a mut Controls);
+
+#[hyperfold_engine::system]
+fn activate_controls(_: &Playing::OnEnter, ControlsCS { controls, .. }: ControlsCS) {
+    controls.active = true;
+}
@@ -0,0 +1,8 @@
+#[repr(u8)]
+pub enum Elevations {
+    Background = 0,
+    Tiles,
+    MergedTiles,
+    Score,
+    GameOverScreen,
+}
@@ -0,0 +1,69 @@
+use hyperfold_engine::framework::event_system::events::Key;
+
+use crate::{
+    _engine::Events,
+    controls::{ControlsCS, Game2048Action},
+    score::save_best_score,
+    tiles::TilesMoved,
+    GameCS, GameOver, GameOverEids, Playing,
+};
+
+/// Number of moves that can be undone
+pub const MAX_UNDO: usize = 50;
+
+#[hyperfold_engine::system]
+fn move_tiles(
+    key: &Key,
+    GameCS { game, .. }: GameCS,
+    ControlsCS { controls, .. }: ControlsCS,
+    game_over: Vec<GameOverEids>,
+    events: &mut dyn Events,
+) {
+    if !key.0.pressed() {
+        return;
+    }
+
+    let dir = match controls.action(key.0.key) {
+        Some(Game2048Action::Move(dir)) => dir,
+        Some(Game2048Action::Undo) => {
+            if let Some(board) = game.history.pop() {
+                game.board = board;
+                events.new_event(TilesMoved::still(&game.board));
+                if !game_over.is_empty() {
+                    events.set_state(Playing::Data);
+                }
+            }
+            return;
+        }
+        Some(Game2048Action::Restart) => {
+            if !game_over.is_empty() {
+                events.set_state(Playing::Data);
+            }
+            return;
+        }
+        None => return,
+    };
+
+    if !game_over.is_empty() {
+        return;
+    }
+
+    let prev = game.board.clone();
+    if let Some(moves) = game.board.shift(dir) {
+        game.history.push(prev);
+        if game.history.len() > MAX_UNDO {
+            game.history.remove(0);
+        }
+        if game.board.score > game.best {
+            game.best = game.board.score;
+            save_best_score(game.best);
+        }
+        events.new_event(TilesMoved {
+            size: game.board.size,
+            events: moves,
+        });
+        if !game.board.can_move() {
+            events.set_state(GameOver::Data);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::entities::NewEntity,
+    framework::{
+        physics::Position,
+        render_system::{
+            drawable::Canvas,
+            font::TIMES,
+            render_data::RenderTexture,
+            shapes::{Rectangle, ShapeTrait},
+            AssetManager, Camera, Elevation, RenderComponent, Renderer, Texture,
+        },
+    },
+    sdl2::SDL_Color,
+    utils::{
+        rand::{new_rng, Rng},
+        rect::{Align, PointF, Rect},
+    },
+};
+
+use ::input::Args;
+use assets::strings::{tr, tr_with};
+use ui::{GameOverText, OverlayLine};
+
+use crate::{
+    board::{Board, Cell, WIN_VALUE},
+    controls::{ControlsCS, Game2048Action},
+    elevations::Elevations,
+    score::load_best_score,
+    tiles::TilesMoved,
+};
+
+pub mod board;
+pub mod controls;
+pub mod elevations;
+pub mod input;
+pub mod score;
+pub mod tiles;
+
+use hyperfold_engine::system_macro;
+
+hyperfold_engine::game_crate!();
+
+pub const DEFAULT_SIZE: usize = 4;
+pub const TILE_W: u32 = 100;
+pub const TILE_F: f32 = TILE_W as f32;
+pub const GAP: f32 = 10.0;
+
+pub const BOARD_COLOR: SDL_Color = SDL_Color {
+    r: 187,
+    g: 173,
+    b: 160,
+    a: 255,
+};
+
+pub const EMPTY_COLOR: SDL_Color = SDL_Color {
+    r: 205,
+    g: 193,
+    b: 180,
+    a: 255,
+};
+
+pub fn board_w(size: usize) -> f32 {
+    size as f32 * TILE_F + (size + 1) as f32 * GAP
+}
+
+/// Returns the center of a cell in world coordinates
+pub fn cell_to_pos((x, y): Cell, size: usize) -> PointF {
+    let start = -board_w(size) / 2.0 + GAP + TILE_F / 2.0;
+    PointF {
+        x: start + x as f32 * (TILE_F + GAP),
+        y: start + y as f32 * (TILE_F + GAP),
+    }
+}
+
+/// Persists between games, the board is reused so it stays visible on the game over screen
+#[hyperfold_engine::component(Singleton)]
+struct Game {
+    pub board: Board,
+    /// Boards before each move, for undo
+    pub history: Vec<Board>,
+    pub best: u32,
+    pub seed: Option<u64>,
+}
+
+impl Game {
+    pub fn new_board(&self) -> Board {
+        Board::new(
+            self.board.size,
+            self.seed.unwrap_or_else(|| new_rng().gen()),
+        )
+    }
+}
+
+components!(GameCS, game: &'a mut Game);
+
+#[hyperfold_engine::system(Init)]
+fn new_game(entities: &mut dyn _engine::Components) {
+    let args = Args::get();
+    let size = args.value("size").unwrap_or(DEFAULT_SIZE).max(2);
+    let seed = args.value("seed");
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Game {
+            board: Board::new(size, seed.unwrap_or_else(|| new_rng().gen())),
+            history: Vec::new(),
+            best: load_best_score(),
+            seed
+        }
+    );
+}
+
+#[hyperfold_engine::state]
+struct Playing;
+
+#[hyperfold_engine::state]
+struct GameOver;
+
+#[hyperfold_engine::component(Singleton)]
+struct Background;
+
+components!(labels(Background), BackgroundEids);
+
+#[hyperfold_engine::system]
+fn start_game(
+    _: &Playing::OnEnter,
+    GameCS { game, .. }: GameCS,
+    bkgrnd: Vec<BackgroundEids>,
+    entities: &mut dyn _engine::Components,
+    events: &mut dyn _engine::Events,
+    r: &Renderer,
+    camera: &mut Camera,
+) {
+    camera.0.set_pos(0.0, 0.0, Align::Center, Align::Center);
+
+    // Coming back from an undo on the game over screen keeps the board
+    if !game.board.can_move() {
+        game.board = game.new_board();
+        game.history.clear();
+    }
+    events.new_event(TilesMoved::still(&game.board));
+
+    if !bkgrnd.is_empty() {
+        return;
+    }
+
+    let size = game.board.size;
+    let w = board_w(size);
+    let tex = Texture::new(r, w as u32, w as u32, BOARD_COLOR);
+    for y in 0..size {
+        for x in 0..size {
+            let mut pos = cell_to_pos((x, y), size);
+            pos.x += w / 2.0;
+            pos.y += w / 2.0;
+            tex.draw(
+                r,
+                &mut Rectangle::new()
+                    .set_color(EMPTY_COLOR)
+                    .fill(Rect::from_center(pos.x, pos.y, TILE_F, TILE_F)),
+            );
+        }
+    }
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Background,
+        Elevation(Elevations::Background as u8),
+        RenderComponent::new(RenderTexture::new(Some(tex))),
+        Position(Rect::from_center(0.0, 0.0, w, w))
+    );
+}
+
+#[hyperfold_engine::component(Singleton)]
+struct GameOverScreen;
+
+components!(labels(GameOverScreen), GameOverEids);
+
+#[hyperfold_engine::system]
+fn game_over(
+    _: &GameOver::OnEnter,
+    GameCS { game, .. }: GameCS,
+    ControlsCS { controls, .. }: ControlsCS,
+    entities: &mut dyn _engine::Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let w = board_w(game.board.size);
+    let w_i = w as u32;
+    let title = tr(match game.board.max_value() >= WIN_VALUE {
+        true => "you_win",
+        false => "game_over",
+    });
+    let game_over = tr("game_over");
+    let hint = tr_with(
+        "game2048_restart_hint",
+        &[
+            ("restart", &controls.bindings.hint(Game2048Action::Restart)),
+            ("undo", &controls.bindings.hint(Game2048Action::Undo)),
+        ],
+    );
+    let tex = GameOverText {
+        title: OverlayLine::new(&title, w_i / 2).with_sample(&game_over),
+        lines: vec![OverlayLine::new(&hint, w_i * 3 / 4)],
+        font: TIMES.to_string(),
+        overlay: SDL_Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 96,
+        },
+    }
+    .texture(w_i, w_i, r, am);
+
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        GameOverScreen,
+        GameOver::Label,
+        Elevation(Elevations::GameOverScreen as u8),
+        RenderComponent::new(RenderTexture::new(Some(tex))),
+        Position(Rect::from_center(0.0, 0.0, w, w))
+    );
+}
@@ -0,0 +1,126 @@
+use std::fs;
+
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::{
+        physics::Position,
+        render_system::{
+            font::TIMES, render_data::RenderTexture, AssetManager, Elevation, RenderComponent,
+            Renderer, Texture,
+        },
+    },
+    utils::{
+        colors::WHITE,
+        rect::{PointF, Rect},
+    },
+};
+
+use assets::{settings::save_file, strings::tr_with};
+use ui::{draw_text, font_data};
+
+use crate::{
+    _engine::Components, board_w, elevations::Elevations, GameCS, Playing, BOARD_COLOR, GAP,
+};
+
+pub const BEST_SCORE_FILE: &str = "save/game2048_best_score.txt";
+pub const SCORE_W: u32 = 300;
+pub const SCORE_H: u32 = 40;
+
+/// A missing or malformed file just means no best score yet
+pub fn load_best_score() -> u32 {
+    fs::read_to_string(BEST_SCORE_FILE)
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn save_best_score(score: u32) {
+    save_file(BEST_SCORE_FILE, "best score", score.to_string());
+}
+
+/// Shows the score and best score above the board
+#[hyperfold_engine::component(Singleton)]
+struct ScoreDisplay {
+    pub score: u32,
+    pub best: u32,
+}
+
+components!(
+    ScoreCS,
+    display: &'a mut ScoreDisplay,
+    tex: &'a mut RenderComponent
+);
+
+#[hyperfold_engine::system]
+fn new_score(
+    _: &Playing::OnEnter,
+    scores: Vec<ScoreCS>,
+    GameCS { game, .. }: GameCS,
+    entities: &mut dyn Components,
+) {
+    if let Some(score) = scores.into_iter().next() {
+        // Force a redraw
+        score.display.score = u32::MAX;
+        return;
+    }
+
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        ScoreDisplay {
+            score: u32::MAX,
+            best: 0
+        },
+        Elevation(Elevations::Score as u8),
+        RenderComponent::new(RenderTexture::new(None)),
+        Position(Rect::from_center(
+            0.0,
+            -board_w(game.board.size) / 2.0 - GAP - SCORE_H as f32 / 2.0,
+            SCORE_W as f32,
+            SCORE_H as f32
+        ))
+    );
+}
+
+#[hyperfold_engine::system]
+fn update_score(
+    _: &Update,
+    score: ScoreCS,
+    GameCS { game, .. }: GameCS,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    if score.display.score == game.board.score && score.display.best == game.best {
+        return;
+    }
+    score.display.score = game.board.score;
+    score.display.best = game.best;
+
+    let tex = Texture::new(r, SCORE_W, SCORE_H, BOARD_COLOR);
+    draw_text(
+        &tex,
+        &tr_with(
+            "game2048_score",
+            &[
+                ("score", &game.board.score.to_string()),
+                ("best", &game.best.to_string()),
+            ],
+        ),
+        font_data(
+            TIMES,
+            &tr_with("game2048_score", &[("score", "000000"), ("best", "000000")]),
+            SCORE_W - 20,
+        ),
+        WHITE,
+        PointF {
+            x: SCORE_W as f32 / 2.0,
+            y: SCORE_H as f32 / 2.0,
+        },
+        r,
+        am,
+    );
+    *score.tex = RenderComponent::new(RenderTexture::new(Some(tex)));
+}
@@ -0,0 +1,238 @@
+use std::f32::consts::PI;
+
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{
+        entities::{EntityTrash, NewEntity},
+        events::core::Update,
+    },
+    framework::{
+        physics::Position,
+        render_system::{
+            font::TIMES, render_data::RenderTexture, AssetManager, Elevation, RenderComponent,
+            Renderer, Texture,
+        },
+    },
+    sdl2::SDL_Color,
+    utils::{
+        colors::WHITE,
+        rect::{PointF, Rect},
+    },
+};
+
+use ui::{draw_text, font_data};
+
+use crate::{
+    _engine::Components,
+    board::{Board, TileEvent},
+    cell_to_pos,
+    elevations::Elevations,
+    TILE_F, TILE_W,
+};
+
+pub const SLIDE_MS: u32 = 100;
+pub const POP_MS: u32 = 150;
+/// How much merged tiles grow at the peak of their pop
+pub const POP_SCALE: f32 = 0.2;
+
+const DARK_TEXT: SDL_Color = SDL_Color {
+    r: 119,
+    g: 110,
+    b: 101,
+    a: 255,
+};
+
+pub fn tile_color(value: u32) -> SDL_Color {
+    let (r, g, b) = match value {
+        2 => (238, 228, 218),
+        4 => (237, 224, 200),
+        8 => (242, 177, 121),
+        16 => (245, 149, 99),
+        32 => (246, 124, 95),
+        64 => (246, 94, 59),
+        128 => (237, 207, 114),
+        256 => (237, 204, 97),
+        512 => (237, 200, 80),
+        1024 => (237, 197, 63),
+        2048 => (237, 194, 46),
+        _ => (60, 58, 50),
+    };
+    SDL_Color { r, g, b, a: 255 }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum TileAnimKind {
+    Slide,
+    /// Slides into a merge then disappears
+    Vanish,
+    /// Appears once sliding finishes and briefly grows
+    Pop,
+    /// Appears once sliding finishes and grows from nothing
+    Spawn,
+}
+
+#[hyperfold_engine::component]
+struct TileAnim {
+    pub kind: TileAnimKind,
+    pub from: PointF,
+    pub to: PointF,
+    pub elapsed: u32,
+}
+
+impl TileAnim {
+    /// Returns the tile center and scale
+    pub fn frame(&self) -> (PointF, f32) {
+        let slide = (self.elapsed as f32 / SLIDE_MS as f32).min(1.0);
+        let after = (self.elapsed.saturating_sub(SLIDE_MS) as f32 / POP_MS as f32).min(1.0);
+        // Ease out so tiles settle into place
+        let t = 1.0 - (1.0 - slide).powi(2);
+        let pos = PointF {
+            x: self.from.x + (self.to.x - self.from.x) * t,
+            y: self.from.y + (self.to.y - self.from.y) * t,
+        };
+        let scale = match self.kind {
+            TileAnimKind::Slide | TileAnimKind::Vanish => 1.0,
+            TileAnimKind::Pop if self.elapsed < SLIDE_MS => 0.0,
+            TileAnimKind::Pop => 1.0 + POP_SCALE * (after * PI).sin(),
+            TileAnimKind::Spawn if self.elapsed < SLIDE_MS => 0.0,
+            TileAnimKind::Spawn => after,
+        };
+        (pos, scale)
+    }
+
+    pub fn done(&self) -> bool {
+        match self.kind {
+            TileAnimKind::Slide | TileAnimKind::Vanish => self.elapsed >= SLIDE_MS,
+            TileAnimKind::Pop | TileAnimKind::Spawn => self.elapsed >= SLIDE_MS + POP_MS,
+        }
+    }
+}
+
+/// Replaces every tile entity with animations for the latest move
+#[hyperfold_engine::event]
+struct TilesMoved {
+    pub size: usize,
+    pub events: Vec<TileEvent>,
+}
+
+impl TilesMoved {
+    /// Shows the board without animating, e.g. after an undo
+    pub fn still(board: &Board) -> Self {
+        Self {
+            size: board.size,
+            events: board
+                .tiles()
+                .map(|(cell, tile)| TileEvent::Slide {
+                    tile,
+                    from: cell,
+                    to: cell,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn tile_texture(value: u32, r: &Renderer, am: &mut AssetManager) -> RenderComponent {
+    let tex = Texture::new(r, TILE_W, TILE_W, tile_color(value));
+    let text_color = if value <= 4 { DARK_TEXT } else { WHITE };
+    draw_text(
+        &tex,
+        &value.to_string(),
+        font_data(TIMES, "2048", TILE_W * 3 / 4),
+        text_color,
+        PointF {
+            x: TILE_F / 2.0,
+            y: TILE_F / 2.0,
+        },
+        r,
+        am,
+    );
+    RenderComponent::new(RenderTexture::new(Some(tex)))
+}
+
+components!(TileAnims, anim: &'a mut TileAnim, pos: &'a mut Position);
+
+#[hyperfold_engine::system]
+fn new_tiles(
+    moved: &TilesMoved,
+    tiles: Vec<TileAnims>,
+    entities: &mut dyn Components,
+    trash: &mut EntityTrash,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    trash.0.extend(tiles.into_iter().map(|tile| *tile.eid));
+
+    let mut add_tile = |value: u32, kind: TileAnimKind, from: PointF, to: PointF| {
+        let anim = TileAnim {
+            kind,
+            from,
+            to,
+            elapsed: 0,
+        };
+        let (pos, scale) = anim.frame();
+        let elevation = match kind {
+            TileAnimKind::Pop => Elevations::MergedTiles,
+            _ => Elevations::Tiles,
+        };
+        let e = Entity::new();
+        add_components!(
+            entities,
+            e,
+            anim,
+            Elevation(elevation as u8),
+            tile_texture(value, r, am),
+            Position(Rect::from_center(
+                pos.x,
+                pos.y,
+                TILE_F * scale,
+                TILE_F * scale
+            ))
+        );
+    };
+
+    let n = moved.size;
+    for event in moved.events.iter() {
+        match *event {
+            TileEvent::Slide { tile, from, to } => add_tile(
+                tile.value,
+                TileAnimKind::Slide,
+                cell_to_pos(from, n),
+                cell_to_pos(to, n),
+            ),
+            TileEvent::Merge { tile, from, to } => {
+                let to = cell_to_pos(to, n);
+                for from in from {
+                    add_tile(
+                        tile.value / 2,
+                        TileAnimKind::Vanish,
+                        cell_to_pos(from, n),
+                        to,
+                    );
+                }
+                add_tile(tile.value, TileAnimKind::Pop, to, to);
+            }
+            TileEvent::Spawn { tile, at } => {
+                let at = cell_to_pos(at, n);
+                add_tile(tile.value, TileAnimKind::Spawn, at, at);
+            }
+        }
+    }
+}
+
+#[hyperfold_engine::system]
+fn animate_tiles(update: &Update, tiles: Vec<TileAnims>, trash: &mut EntityTrash) {
+    for TileAnims { eid, anim, pos } in tiles {
+        if anim.done() {
+            continue;
+        }
+        anim.elapsed += update.0;
+        if anim.kind == TileAnimKind::Vanish && anim.done() {
+            trash.0.push(*eid);
+            continue;
+        }
+        let (center, scale) = anim.frame();
+        pos.0 = Rect::from_center(center.x, center.y, TILE_F * scale, TILE_F * scale);
+    }
+}
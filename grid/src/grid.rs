@@ -0,0 +1,290 @@
+use std::{
+    collections::VecDeque,
+    ops::{Index, IndexMut},
+};
+
+use hyperfold_engine::utils::rect::Point;
+
+pub const NEIGHBOURS_4: [Point; 4] = [
+    Point { x: 0, y: -1 },
+    Point { x: -1, y: 0 },
+    Point { x: 1, y: 0 },
+    Point { x: 0, y: 1 },
+];
+
+pub const NEIGHBOURS_8: [Point; 8] = [
+    Point { x: -1, y: -1 },
+    Point { x: 0, y: -1 },
+    Point { x: 1, y: -1 },
+    Point { x: -1, y: 0 },
+    Point { x: 1, y: 0 },
+    Point { x: -1, y: 1 },
+    Point { x: 0, y: 1 },
+    Point { x: 1, y: 1 },
+];
+
+/// Row-major container with one `T` per cell
+#[derive(Clone)]
+pub struct Grid<T> {
+    w: u32,
+    h: u32,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(w: u32, h: u32, val: T) -> Self {
+        Self {
+            w,
+            h,
+            cells: vec![val; (w * h) as usize],
+        }
+    }
+
+    /// Resets every cell to `val`
+    pub fn fill(&mut self, val: T) {
+        self.cells.fill(val);
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(w: u32, h: u32, mut f: impl FnMut(Point) -> T) -> Self {
+        Self {
+            w,
+            h,
+            cells: (0..h as i32)
+                .flat_map(|y| (0..w as i32).map(move |x| Point { x, y }))
+                .map(&mut f)
+                .collect(),
+        }
+    }
+
+    pub fn w(&self) -> u32 {
+        self.w
+    }
+
+    pub fn h(&self) -> u32 {
+        self.h
+    }
+
+    pub fn in_bounds(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as u32) < self.w && (p.y as u32) < self.h
+    }
+
+    fn idx(&self, p: Point) -> Option<usize> {
+        self.in_bounds(p)
+            .then(|| p.y as usize * self.w as usize + p.x as usize)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.idx(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.idx(p).map(|i| &mut self.cells[i])
+    }
+
+    /// Returns every cell position, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let w = self.w as i32;
+        (0..self.h as i32).flat_map(move |y| (0..w).map(move |x| Point { x, y }))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut T)> {
+        self.points().zip(self.cells.iter_mut())
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(Point, &T) -> U) -> Grid<U> {
+        Grid {
+            w: self.w,
+            h: self.h,
+            cells: self.iter().map(|(p, t)| f(p, t)).collect(),
+        }
+    }
+
+    fn offsets<'a>(&'a self, p: Point, offsets: &'a [Point]) -> impl Iterator<Item = Point> + 'a {
+        offsets
+            .iter()
+            .map(move |d| Point {
+                x: p.x + d.x,
+                y: p.y + d.y,
+            })
+            .filter(|n| self.in_bounds(*n))
+    }
+
+    /// In-bounds orthogonal neighbours
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        self.offsets(p, &NEIGHBOURS_4)
+    }
+
+    /// In-bounds orthogonal and diagonal neighbours
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        self.offsets(p, &NEIGHBOURS_8)
+    }
+
+    /// Returns every cell orthogonally connected to `start` through cells where `passable` is true
+    /// `start` is included if it is passable
+    pub fn flood_fill(&self, start: Point, passable: impl Fn(Point, &T) -> bool) -> Vec<Point> {
+        let mut seen = Grid::new(self.w, self.h, false);
+        let mut filled = Vec::new();
+        let mut stack = vec![start];
+        while let Some(p) = stack.pop() {
+            match (self.get(p), seen.get_mut(p)) {
+                (Some(t), Some(seen)) if !*seen && passable(p, t) => *seen = true,
+                _ => continue,
+            }
+            filled.push(p);
+            stack.extend(self.neighbours4(p));
+        }
+        filled
+    }
+
+    /// Breadth first search from every source at once
+    /// Each cell holds the number of orthogonal steps to the nearest source,
+    /// or None if it is impassable or unreachable
+    pub fn distance_map(
+        &self,
+        sources: impl IntoIterator<Item = Point>,
+        passable: impl Fn(Point, &T) -> bool,
+    ) -> Grid<Option<u32>> {
+        let mut dists = Grid::new(self.w, self.h, None);
+        let mut queue = VecDeque::new();
+        for p in sources {
+            if let Some(d @ None) = dists.get_mut(p) {
+                *d = Some(0);
+                queue.push_back(p);
+            }
+        }
+        while let Some(p) = queue.pop_front() {
+            let dist = dists[p].unwrap_or(0) + 1;
+            for n in self.neighbours4(p) {
+                if dists[n].is_none() && passable(n, &self[n]) {
+                    dists[n] = Some(dist);
+                    queue.push_back(n);
+                }
+            }
+        }
+        dists
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        self.get(p)
+            .unwrap_or_else(|| panic!("Grid index ({}, {}) out of bounds", p.x, p.y))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        let (x, y) = (p.x, p.y);
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("Grid index ({x}, {y}) out of bounds"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `#` is a wall, anything else is open
+    fn walls(rows: &[&str]) -> Grid<bool> {
+        let (w, h) = (rows[0].len() as u32, rows.len() as u32);
+        Grid::from_fn(w, h, |p| {
+            rows[p.y as usize].as_bytes()[p.x as usize] == b'#'
+        })
+    }
+
+    fn open(_: Point, wall: &bool) -> bool {
+        !*wall
+    }
+
+    fn sorted(points: Vec<Point>) -> Vec<(i32, i32)> {
+        let mut points: Vec<_> = points.into_iter().map(|p| (p.x, p.y)).collect();
+        points.sort();
+        points
+    }
+
+    fn dists(grid: &Grid<Option<u32>>) -> Vec<Vec<Option<u32>>> {
+        (0..grid.h() as i32)
+            .map(|y| (0..grid.w() as i32).map(|x| grid[Point { x, y }]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn flood_fill_stops_at_walls_and_edges() {
+        let grid = walls(&["..#.", "..#.", "###."]);
+        let filled = grid.flood_fill(Point { x: 0, y: 0 }, open);
+        assert_eq!(sorted(filled), [(0, 0), (0, 1), (1, 0), (1, 1)]);
+
+        let filled = grid.flood_fill(Point { x: 3, y: 2 }, open);
+        assert_eq!(sorted(filled), [(3, 0), (3, 1), (3, 2)]);
+    }
+
+    #[test]
+    fn flood_fill_start_cell() {
+        let grid = walls(&[".#", "#."]);
+        // Diagonal cells aren't connected
+        let filled = grid.flood_fill(Point { x: 0, y: 0 }, open);
+        assert_eq!(sorted(filled), [(0, 0)]);
+        assert!(grid.flood_fill(Point { x: 1, y: 0 }, open).is_empty());
+        assert!(grid.flood_fill(Point { x: -1, y: 0 }, open).is_empty());
+        assert!(grid.flood_fill(Point { x: 2, y: 1 }, open).is_empty());
+    }
+
+    #[test]
+    fn distance_map_counts_steps_around_walls() {
+        let grid = walls(&["...", ".#.", "..#"]);
+        let map = grid.distance_map([Point { x: 0, y: 0 }], open);
+        assert_eq!(
+            dists(&map),
+            [
+                [Some(0), Some(1), Some(2)],
+                [Some(1), None, Some(3)],
+                [Some(2), Some(3), None],
+            ]
+        );
+    }
+
+    #[test]
+    fn distance_map_unreachable_cells() {
+        let grid = walls(&[".#.", "##.", "..."]);
+        let map = grid.distance_map([Point { x: 2, y: 2 }], open);
+        assert_eq!(
+            dists(&map),
+            [
+                [None, None, Some(2)],
+                [None, None, Some(1)],
+                [Some(2), Some(1), Some(0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn distance_map_sources() {
+        let grid = walls(&[".....", "....#"]);
+        // The nearest source wins, sources out of bounds are ignored
+        let sources = [
+            Point { x: 0, y: 0 },
+            Point { x: 4, y: 0 },
+            Point { x: 9, y: 9 },
+        ];
+        let map = grid.distance_map(sources, open);
+        assert_eq!(
+            dists(&map),
+            [
+                [Some(0), Some(1), Some(2), Some(1), Some(0)],
+                [Some(1), Some(2), Some(3), Some(2), None],
+            ]
+        );
+
+        let grid = walls(&["#."]);
+        let map = grid.distance_map([], open);
+        assert_eq!(dists(&map), [[None, None]]);
+    }
+}
@@ -0,0 +1,60 @@
+use hyperfold_engine::{
+    framework::render_system::Camera,
+    utils::rect::{Align, Point, PointF, Rect},
+};
+
+/// Maps grid cells to world space for a board centered on the camera
+#[derive(Copy, Clone)]
+pub struct GridLayout {
+    pub cell_w: f32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl GridLayout {
+    pub const fn new(cell_w: f32, w: u32, h: u32) -> Self {
+        Self { cell_w, w, h }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.cell_w * self.w as f32
+    }
+
+    pub fn height(&self) -> f32 {
+        self.cell_w * self.h as f32
+    }
+
+    pub fn contains(&self, cell: Point) -> bool {
+        cell.x >= 0 && cell.y >= 0 && (cell.x as u32) < self.w && (cell.y as u32) < self.h
+    }
+
+    /// The board in world space
+    pub fn rect(&self, camera: &Camera) -> Rect {
+        Rect::from(
+            camera.0.cx(),
+            camera.0.cy(),
+            self.width(),
+            self.height(),
+            Align::Center,
+            Align::Center,
+        )
+    }
+
+    /// Returns the cell containing `pos`, which may be out of bounds
+    pub fn world_to_cell(&self, pos: PointF, camera: &Camera) -> Point {
+        let rect = self.rect(camera);
+        Point {
+            x: ((pos.x - rect.x) / self.cell_w).floor() as i32,
+            y: ((pos.y - rect.y) / self.cell_w).floor() as i32,
+        }
+    }
+
+    /// Returns the center of `cell`
+    pub fn cell_to_world(&self, cell: Point, camera: &Camera) -> PointF {
+        let rect = self.rect(camera);
+        PointF {
+            x: rect.x + self.cell_w * (cell.x as f32 + 0.5),
+            y: rect.y + self.cell_w * (cell.y as f32 + 0.5),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+pub mod grid;
+pub mod layout;
+pub mod render;
+pub mod spatial_hash;
+pub mod viewport;
+
+pub use grid::{Grid, NEIGHBOURS_4, NEIGHBOURS_8};
+pub use layout::GridLayout;
+pub use render::{grid_texture, GridStyle};
+pub use spatial_hash::SpatialHash;
+pub use viewport::{Scaling, Viewport};
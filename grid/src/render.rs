@@ -0,0 +1,53 @@
+use hyperfold_engine::{
+    framework::render_system::{
+        drawable::Canvas,
+        shapes::{Rectangle, ShapeTrait},
+        Renderer, Texture,
+    },
+    sdl2::SDL_Color,
+    utils::{colors::gray, rect::Rect},
+};
+
+use crate::GridLayout;
+
+#[derive(Copy, Clone)]
+pub struct GridStyle {
+    pub background: SDL_Color,
+    pub line: SDL_Color,
+    /// Negative widths draw inside each cell
+    pub line_w: f32,
+}
+
+impl Default for GridStyle {
+    fn default() -> Self {
+        Self {
+            background: gray(100),
+            line: gray(200),
+            line_w: -2.0,
+        }
+    }
+}
+
+/// Bakes the board background, one bordered square per cell
+pub fn grid_texture(layout: &GridLayout, style: &GridStyle, r: &Renderer) -> Texture {
+    let tex = Texture::new(
+        r,
+        layout.width() as u32,
+        layout.height() as u32,
+        style.background,
+    );
+    let w = layout.cell_w;
+    for x in (0..layout.w).map(|x| x as f32 * w) {
+        for y in (0..layout.h).map(|y| y as f32 * w) {
+            tex.draw(
+                r,
+                &mut Rectangle::new().set_color(style.line).border(
+                    Rect { x, y, w, h: w },
+                    style.line_w,
+                    false,
+                ),
+            );
+        }
+    }
+    tex
+}
@@ -0,0 +1,184 @@
+use std::{collections::HashMap, hash::Hash};
+
+use hyperfold_engine::utils::rect::Rect;
+
+/// The cells a box covers, inclusive
+#[derive(Copy, Clone, PartialEq, Eq)]
+struct CellRange {
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+}
+
+impl CellRange {
+    fn cells(self) -> impl Iterator<Item = (i32, i32)> {
+        (self.y0..=self.y1).flat_map(move |y| (self.x0..=self.x1).map(move |x| (x, y)))
+    }
+}
+
+/// Buckets boxes by the square cells they cover, so overlap checks only look nearby
+/// Moving a box only touches the buckets it leaves and enters
+pub struct SpatialHash<K> {
+    cell_w: f32,
+    buckets: HashMap<(i32, i32), Vec<K>>,
+    entries: HashMap<K, (Rect, CellRange)>,
+}
+
+impl<K: Copy + Eq + Hash> SpatialHash<K> {
+    /// Cells about the size of the most common box keep buckets small
+    pub fn new(cell_w: f32) -> Self {
+        Self {
+            cell_w: cell_w.max(1.0),
+            buckets: HashMap::new(),
+            entries: HashMap::new(),
+        }
+    }
+
+    fn range(&self, rect: &Rect) -> CellRange {
+        let cell = |v: f32| (v / self.cell_w).floor() as i32;
+        CellRange {
+            x0: cell(rect.x),
+            y0: cell(rect.y),
+            x1: cell(rect.x + rect.w),
+            y1: cell(rect.y + rect.h),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: K) -> Option<Rect> {
+        self.entries.get(&key).map(|(rect, _)| *rect)
+    }
+
+    /// Adds `key`, or moves it if it is already in the hash
+    pub fn insert(&mut self, key: K, rect: Rect) {
+        let range = self.range(&rect);
+        match self.entries.insert(key, (rect, range)) {
+            Some((_, old)) if old == range => return,
+            Some((_, old)) => self.unlink(key, old),
+            None => (),
+        }
+        for cell in range.cells() {
+            self.buckets.entry(cell).or_default().push(key);
+        }
+    }
+
+    pub fn remove(&mut self, key: K) {
+        if let Some((_, range)) = self.entries.remove(&key) {
+            self.unlink(key, range);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.entries.clear();
+    }
+
+    fn unlink(&mut self, key: K, range: CellRange) {
+        for cell in range.cells() {
+            let Some(bucket) = self.buckets.get_mut(&cell) else {
+                continue;
+            };
+            if let Some(i) = bucket.iter().position(|k| *k == key) {
+                bucket.swap_remove(i);
+            }
+            if bucket.is_empty() {
+                self.buckets.remove(&cell);
+            }
+        }
+    }
+
+    /// Keys whose boxes intersect `rect`, each reported once
+    pub fn query<'a>(&'a self, rect: &'a Rect) -> impl Iterator<Item = K> + 'a {
+        let range = self.range(rect);
+        range
+            .cells()
+            .filter_map(|cell| self.buckets.get(&cell).map(|bucket| (cell, bucket)))
+            .flat_map(move |(cell, bucket)| {
+                bucket.iter().filter_map(move |key| {
+                    let (key_rect, key_range) = &self.entries[key];
+                    // A box in several cells is only reported from the first cell both ranges share
+                    let first = (key_range.x0.max(range.x0), key_range.y0.max(range.y0)) == cell;
+                    (first && key_rect.intersects(rect)).then_some(*key)
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect { x, y, w, h }
+    }
+
+    fn query(hash: &SpatialHash<u32>, rect: Rect) -> Vec<u32> {
+        let mut keys: Vec<_> = hash.query(&rect).collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn boxes_in_several_cells_are_reported_once() {
+        let mut hash = SpatialHash::new(10.0);
+        // 3 by 3 cells, 2 by 1 cells and inside one cell
+        hash.insert(0, rect(5.0, 5.0, 20.0, 20.0));
+        hash.insert(1, rect(12.0, 31.0, 10.0, 5.0));
+        hash.insert(2, rect(41.0, 41.0, 8.0, 8.0));
+
+        // Covering every cell of every box
+        assert_eq!(query(&hash, rect(0.0, 0.0, 50.0, 50.0)), [0, 1, 2]);
+        // Sharing only some of box 0's cells, starting past its first one
+        assert_eq!(query(&hash, rect(15.0, 15.0, 30.0, 30.0)), [0, 1, 2]);
+        assert_eq!(query(&hash, rect(22.0, 22.0, 1.0, 1.0)), [0]);
+        // A single cell inside box 0
+        assert_eq!(query(&hash, rect(11.0, 11.0, 8.0, 8.0)), [0]);
+    }
+
+    #[test]
+    fn shared_cells_without_overlap_are_not_reported() {
+        let mut hash = SpatialHash::new(10.0);
+        hash.insert(0, rect(0.0, 0.0, 4.0, 4.0));
+        hash.insert(1, rect(5.0, 5.0, 25.0, 3.0));
+        assert_eq!(query(&hash, rect(6.0, 0.0, 3.0, 3.0)), []);
+        assert_eq!(query(&hash, rect(25.0, 8.5, 10.0, 10.0)), []);
+        assert_eq!(query(&hash, rect(25.0, 7.0, 10.0, 10.0)), [1]);
+    }
+
+    #[test]
+    fn moved_and_removed_boxes() {
+        let mut hash = SpatialHash::new(10.0);
+        hash.insert(0, rect(5.0, 5.0, 20.0, 20.0));
+        hash.insert(1, rect(5.0, 5.0, 20.0, 20.0));
+        // Moving into cells it partly covered already
+        hash.insert(0, rect(15.0, 15.0, 20.0, 20.0));
+        assert_eq!(query(&hash, rect(0.0, 0.0, 50.0, 50.0)), [0, 1]);
+        assert_eq!(query(&hash, rect(6.0, 6.0, 2.0, 2.0)), [1]);
+        assert_eq!(query(&hash, rect(30.0, 30.0, 2.0, 2.0)), [0]);
+        assert_eq!(hash.len(), 2);
+
+        hash.remove(1);
+        assert_eq!(query(&hash, rect(0.0, 0.0, 50.0, 50.0)), [0]);
+        hash.remove(0);
+        assert!(hash.is_empty());
+        assert_eq!(query(&hash, rect(0.0, 0.0, 50.0, 50.0)), []);
+    }
+
+    #[test]
+    fn negative_cells() {
+        let mut hash = SpatialHash::new(10.0);
+        hash.insert(0, Rect::from_center(0.0, 0.0, 20.0, 20.0));
+        hash.insert(1, Rect::from_center(-30.0, -30.0, 5.0, 5.0));
+        assert_eq!(query(&hash, rect(-50.0, -50.0, 100.0, 100.0)), [0, 1]);
+        assert_eq!(query(&hash, rect(-9.0, -9.0, 1.0, 1.0)), [0]);
+        assert_eq!(query(&hash, rect(-32.0, -29.0, 1.0, 1.0)), [1]);
+    }
+}
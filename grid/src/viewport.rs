@@ -0,0 +1,53 @@
+/// How the board is scaled to fill the window
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scaling {
+    /// Whole multiples only, keeps pixel art sharp
+    Integer,
+    /// As large as fits
+    Fractional,
+}
+
+impl Scaling {
+    /// Name used in settings files
+    pub fn id(&self) -> &'static str {
+        match self {
+            Scaling::Integer => "integer",
+            Scaling::Fractional => "fractional",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        [Scaling::Integer, Scaling::Fractional]
+            .into_iter()
+            .find(|s| s.id() == id)
+    }
+}
+
+/// The part of the world shown in the window
+/// World units stay the same at every scale, only the camera size changes
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+    /// Window pixels per world unit
+    pub scale: f32,
+    /// Camera size in world units, the space around the board is letterboxed
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Viewport {
+    /// Fits a `board_w` by `board_h` board in the window, centered with bars on the sides that don't fill
+    /// Integer scaling falls back to fractional in windows smaller than the board
+    pub fn fit(board_w: f32, board_h: f32, window_w: u32, window_h: u32, scaling: Scaling) -> Self {
+        let (window_w, window_h) = (window_w.max(1) as f32, window_h.max(1) as f32);
+        let fit = (window_w / board_w.max(1.0)).min(window_h / board_h.max(1.0));
+        let scale = match scaling {
+            Scaling::Integer if fit >= 1.0 => fit.floor(),
+            _ => fit,
+        };
+        Self {
+            scale,
+            w: window_w / scale,
+            h: window_h / scale,
+        }
+    }
+}
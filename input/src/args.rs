@@ -0,0 +1,109 @@
+use std::{env, iter::Peekable, str::FromStr, sync::OnceLock};
+
+/// Command line arguments, `[game] [--<name> <value>|--<name>=<value>]...` in any order
+/// Parsed in one place so the launcher and the games agree on which argument is which
+pub struct Args {
+    game: Option<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut parsed = Self {
+            game: None,
+            options: Vec::new(),
+        };
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(option) => parsed.option(option, &mut args),
+                None if parsed.game.is_none() => parsed.game = Some(arg),
+                None => eprintln!("Ignoring unexpected argument '{arg}'"),
+            }
+        }
+        parsed
+    }
+
+    /// Reads the value after `--<option>` unless it is joined with `=`
+    fn option(&mut self, option: &str, args: &mut Peekable<impl Iterator<Item = String>>) {
+        if let Some((name, value)) = option.split_once('=') {
+            self.options.push((name.to_string(), value.to_string()));
+            return;
+        }
+        match args.next_if(|value| !value.starts_with("--")) {
+            Some(value) => self.options.push((option.to_string(), value)),
+            None => eprintln!("Ignoring --{option}: missing value"),
+        }
+    }
+
+    /// The process's arguments, parsed on first use
+    pub fn get() -> &'static Self {
+        static ARGS: OnceLock<Args> = OnceLock::new();
+        ARGS.get_or_init(|| Self::parse(env::args().skip(1)))
+    }
+
+    /// The first argument that isn't an option or its value
+    pub fn game(&self) -> Option<&str> {
+        self.game.as_deref()
+    }
+
+    /// The last value given for `--<name>`, None if there isn't one or it doesn't parse
+    pub fn value<T: FromStr>(&self, name: &str) -> Option<T> {
+        let (_, value) = self.options.iter().rev().find(|(n, _)| n == name)?;
+        let parsed = value.parse().ok();
+        if parsed.is_none() {
+            eprintln!("Ignoring --{name}: '{value}' is not a valid value");
+        }
+        parsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn game_before_options() {
+        let args = parse(&["minesweeper", "--seed", "5"]);
+        assert_eq!(args.game(), Some("minesweeper"));
+        assert_eq!(args.value::<u64>("seed"), Some(5));
+    }
+
+    #[test]
+    fn game_after_options() {
+        let args = parse(&["--seed", "5", "--size=6", "2048"]);
+        assert_eq!(args.game(), Some("2048"));
+        assert_eq!(args.value::<u64>("seed"), Some(5));
+        assert_eq!(args.value::<usize>("size"), Some(6));
+    }
+
+    #[test]
+    fn no_game() {
+        let args = parse(&["--seed", "5"]);
+        assert_eq!(args.game(), None);
+    }
+
+    #[test]
+    fn missing_value() {
+        let args = parse(&["--seed", "--size", "4", "breakout"]);
+        assert_eq!(args.value::<u64>("seed"), None);
+        assert_eq!(args.value::<usize>("size"), Some(4));
+        assert_eq!(args.game(), Some("breakout"));
+
+        let args = parse(&["snake", "--seed"]);
+        assert_eq!(args.value::<u64>("seed"), None);
+        assert_eq!(args.game(), Some("snake"));
+    }
+
+    #[test]
+    fn invalid_and_repeated_values() {
+        let args = parse(&["--seed", "abc", "--size", "4", "--size", "5"]);
+        assert_eq!(args.value::<u64>("seed"), None);
+        assert_eq!(args.value::<usize>("size"), Some(5));
+        assert_eq!(args.value::<usize>("other"), None);
+    }
+}
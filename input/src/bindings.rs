@@ -0,0 +1,215 @@
+use std::{
+    ffi::{CStr, CString},
+    fs,
+};
+
+use assets::{settings::save_file, strings::tr};
+
+use crate::{gamepad::GamepadButton, sdl_sys::*};
+
+/// Shared by every game, each game has its own `[game]` section
+pub const CONTROLS_FILE: &str = "save/controls.txt";
+/// Written for actions that were unbound on purpose
+pub const UNBOUND: &str = "none";
+const PAD_PREFIX: &str = "Pad ";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    /// SDL keycode
+    Key(i32),
+    Button(GamepadButton),
+}
+
+impl Binding {
+    /// The SDL key name, e.g. "W" or "Left Shift", or "Pad " and the button name
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => {
+                // SAFETY: SDL always returns a valid, possibly empty, string
+                unsafe { CStr::from_ptr(SDL_GetKeyName(*key)) }
+                    .to_string_lossy()
+                    .to_string()
+            }
+            Binding::Button(button) => format!("{PAD_PREFIX}{}", button.name()),
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        if let Some(button) = name.strip_prefix(PAD_PREFIX) {
+            return GamepadButton::from_name(button).map(Binding::Button);
+        }
+        let name = CString::new(name).ok()?;
+        match unsafe { SDL_GetKeyFromName(name.as_ptr()) } {
+            SDLK_UNKNOWN => None,
+            key => Some(Binding::Key(key)),
+        }
+    }
+}
+
+/// An action a game can bind inputs to
+pub trait Action: Copy + Eq + 'static {
+    /// Name of the game's section in the controls file
+    const GAME: &'static str;
+    const ALL: &'static [Self];
+
+    /// Name used in the controls file
+    fn id(&self) -> &'static str;
+    fn defaults(&self) -> Vec<Binding>;
+}
+
+/// Maps inputs to actions, each input triggers at most one action
+pub struct Bindings<A: Action> {
+    bindings: Vec<(A, Vec<Binding>)>,
+}
+
+impl<A: Action> Default for Bindings<A> {
+    fn default() -> Self {
+        Self {
+            bindings: A::ALL.iter().map(|a| (*a, a.defaults())).collect(),
+        }
+    }
+}
+
+impl<A: Action> Bindings<A> {
+    /// Reads `<action> <binding>` lines from this game's section
+    /// Actions missing from the file keep their defaults, so new actions are bound after updates
+    /// A missing or malformed file just means default bindings
+    pub fn load() -> Self {
+        let text = fs::read_to_string(CONTROLS_FILE).unwrap_or_default();
+        if !text.lines().any(|line| header(line) == Some(A::GAME)) {
+            return Self::default();
+        }
+
+        let mut bindings = Self {
+            bindings: A::ALL.iter().map(|a| (*a, Vec::new())).collect(),
+        };
+        let mut listed: Vec<A> = Vec::new();
+        for line in section(&text, A::GAME) {
+            let Some((id, name)) = line.trim().split_once(' ') else {
+                continue;
+            };
+            let Some(action) = A::ALL.iter().find(|a| a.id() == id) else {
+                continue;
+            };
+            listed.push(*action);
+            // Bindings edited into conflicts by hand are dropped
+            if let Some(binding) = Binding::parse(name.trim()) {
+                let _ = bindings.bind(*action, binding);
+            }
+        }
+        for action in A::ALL.iter().filter(|a| !listed.contains(a)) {
+            for binding in action.defaults() {
+                let _ = bindings.bind(*action, binding);
+            }
+        }
+        bindings
+    }
+
+    /// Rewrites this game's section, keeping the other games' sections
+    pub fn save(&self) {
+        let text = fs::read_to_string(CONTROLS_FILE).unwrap_or_default();
+        let mut out = String::new();
+        let mut skip = false;
+        for line in text.lines() {
+            if let Some(game) = header(line) {
+                skip = game == A::GAME;
+            }
+            if !skip {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        out.push_str(&format!("[{}]\n", A::GAME));
+        for (action, bindings) in self.bindings.iter() {
+            if bindings.is_empty() {
+                out.push_str(&format!("{} {UNBOUND}\n", action.id()));
+            }
+            for binding in bindings {
+                out.push_str(&format!("{} {}\n", action.id(), binding.name()));
+            }
+        }
+
+        save_file(CONTROLS_FILE, "controls", out);
+    }
+
+    pub fn get(&self, action: A) -> &[Binding] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map_or(&[], |(_, bindings)| bindings.as_slice())
+    }
+
+    /// First binding for `action`, for hints like "Press R to restart"
+    pub fn hint(&self, action: A) -> String {
+        self.get(action)
+            .first()
+            .map_or_else(|| tr("controls_unbound"), |b| b.name())
+    }
+
+    fn get_mut(&mut self, action: A) -> &mut Vec<Binding> {
+        let i = self
+            .bindings
+            .iter()
+            .position(|(a, _)| *a == action)
+            .unwrap_or_else(|| panic!("{} is missing from Action::ALL", action.id()));
+        &mut self.bindings[i].1
+    }
+
+    /// The action `binding` triggers
+    pub fn action(&self, binding: Binding) -> Option<A> {
+        self.bindings
+            .iter()
+            .find(|(_, bindings)| bindings.contains(&binding))
+            .map(|(a, _)| *a)
+    }
+
+    /// Adds `binding` to `action`
+    /// Fails with the action that already uses it
+    pub fn bind(&mut self, action: A, binding: Binding) -> Result<(), A> {
+        match self.action(binding) {
+            Some(a) if a == action => Ok(()),
+            Some(a) => Err(a),
+            None => {
+                self.get_mut(action).push(binding);
+                Ok(())
+            }
+        }
+    }
+
+    /// Replaces the key or gamepad button bound to `action` with `binding`, whichever it is
+    /// Fails with the action that already uses it
+    pub fn replace(&mut self, action: A, binding: Binding) -> Result<(), A> {
+        match self.action(binding) {
+            Some(a) if a != action => return Err(a),
+            _ => (),
+        }
+        let is_key = |b: &Binding| matches!(b, Binding::Key(_));
+        let bindings = self.get_mut(action);
+        bindings.retain(|b| is_key(b) != is_key(&binding));
+        bindings.push(binding);
+        Ok(())
+    }
+
+    /// Goes back to the default bindings for `action`
+    /// Defaults taken by another action are skipped, the first such action is returned
+    pub fn reset(&mut self, action: A) -> Result<(), A> {
+        self.get_mut(action).clear();
+        action
+            .defaults()
+            .into_iter()
+            .map(|binding| self.bind(action, binding))
+            .fold(Ok(()), Result::and)
+    }
+}
+
+fn header(line: &str) -> Option<&str> {
+    line.trim().strip_prefix('[')?.strip_suffix(']')
+}
+
+/// Lines between `[game]` and the next header
+fn section<'a>(text: &'a str, game: &'a str) -> impl Iterator<Item = &'a str> {
+    text.lines()
+        .skip_while(move |line| header(line) != Some(game))
+        .skip(1)
+        .take_while(|line| header(line).is_none())
+}
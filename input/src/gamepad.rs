@@ -0,0 +1,289 @@
+use std::os::raw::c_int;
+
+use crate::sdl_sys::*;
+
+/// Fraction of the stick's range that is ignored around the center
+pub const DEFAULT_DEAD_ZONE: f32 = 0.25;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// A on Xbox, Cross on PlayStation
+    South,
+    East,
+    West,
+    North,
+    Back,
+    Start,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+impl GamepadButton {
+    pub const ALL: [GamepadButton; 12] = [
+        GamepadButton::South,
+        GamepadButton::East,
+        GamepadButton::West,
+        GamepadButton::North,
+        GamepadButton::Back,
+        GamepadButton::Start,
+        GamepadButton::LeftShoulder,
+        GamepadButton::RightShoulder,
+        GamepadButton::DPadUp,
+        GamepadButton::DPadDown,
+        GamepadButton::DPadLeft,
+        GamepadButton::DPadRight,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GamepadButton::South => "South",
+            GamepadButton::East => "East",
+            GamepadButton::West => "West",
+            GamepadButton::North => "North",
+            GamepadButton::Back => "Back",
+            GamepadButton::Start => "Start",
+            GamepadButton::LeftShoulder => "LeftShoulder",
+            GamepadButton::RightShoulder => "RightShoulder",
+            GamepadButton::DPadUp => "DPadUp",
+            GamepadButton::DPadDown => "DPadDown",
+            GamepadButton::DPadLeft => "DPadLeft",
+            GamepadButton::DPadRight => "DPadRight",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|b| b.name() == name)
+    }
+
+    fn sdl_button(&self) -> c_int {
+        match self {
+            GamepadButton::South => SDL_CONTROLLER_BUTTON_A,
+            GamepadButton::East => SDL_CONTROLLER_BUTTON_B,
+            GamepadButton::West => SDL_CONTROLLER_BUTTON_X,
+            GamepadButton::North => SDL_CONTROLLER_BUTTON_Y,
+            GamepadButton::Back => SDL_CONTROLLER_BUTTON_BACK,
+            GamepadButton::Start => SDL_CONTROLLER_BUTTON_START,
+            GamepadButton::LeftShoulder => SDL_CONTROLLER_BUTTON_LEFTSHOULDER,
+            GamepadButton::RightShoulder => SDL_CONTROLLER_BUTTON_RIGHTSHOULDER,
+            GamepadButton::DPadUp => SDL_CONTROLLER_BUTTON_DPAD_UP,
+            GamepadButton::DPadDown => SDL_CONTROLLER_BUTTON_DPAD_DOWN,
+            GamepadButton::DPadLeft => SDL_CONTROLLER_BUTTON_DPAD_LEFT,
+            GamepadButton::DPadRight => SDL_CONTROLLER_BUTTON_DPAD_RIGHT,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PadDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl PadDirection {
+    /// Direction of the stick at `(x, y)`, both from -1 to 1 with y pointing down
+    /// None inside the dead zone
+    pub fn from_stick(x: f32, y: f32, dead_zone: f32) -> Option<Self> {
+        if x * x + y * y < dead_zone * dead_zone {
+            return None;
+        }
+        Some(match x.abs() >= y.abs() {
+            true if x < 0.0 => PadDirection::Left,
+            true => PadDirection::Right,
+            false if y < 0.0 => PadDirection::Up,
+            false => PadDirection::Down,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GamepadEvent {
+    Connected {
+        player: usize,
+    },
+    Disconnected {
+        player: usize,
+    },
+    Pressed {
+        player: usize,
+        button: GamepadButton,
+    },
+    Released {
+        player: usize,
+        button: GamepadButton,
+    },
+    /// The left stick was pushed in a new direction, the D-pad is reported as buttons
+    Direction {
+        player: usize,
+        dir: PadDirection,
+    },
+}
+
+struct Gamepad {
+    controller: *mut SDL_GameController,
+    id: SDL_JoystickID,
+    /// Bit per `GamepadButton::ALL`
+    buttons: u32,
+    dir: Option<PadDirection>,
+}
+
+impl Gamepad {
+    fn buttons(&self) -> u32 {
+        GamepadButton::ALL
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| unsafe {
+                SDL_GameControllerGetButton(self.controller, b.sdl_button()) != 0
+            })
+            .fold(0, |bits, (i, _)| bits | 1 << i)
+    }
+
+    fn stick(&self, axis: c_int) -> f32 {
+        unsafe { SDL_GameControllerGetAxis(self.controller, axis) as f32 / i16::MAX as f32 }
+    }
+
+    fn direction(&self, dead_zone: f32) -> Option<PadDirection> {
+        PadDirection::from_stick(
+            self.stick(SDL_CONTROLLER_AXIS_LEFTX),
+            self.stick(SDL_CONTROLLER_AXIS_LEFTY),
+            dead_zone,
+        )
+    }
+}
+
+impl Drop for Gamepad {
+    fn drop(&mut self) {
+        unsafe { SDL_GameControllerClose(self.controller) }
+    }
+}
+
+/// Opens controllers as they are plugged in and assigns each to the lowest free player slot
+/// If the controller subsystem can't start every call is a no-op
+pub struct Gamepads {
+    open: bool,
+    players: Vec<Option<Gamepad>>,
+    pub dead_zone: f32,
+}
+
+impl Gamepads {
+    /// Controllers past `max_players` are left closed until a slot frees up
+    pub fn new(max_players: usize) -> Self {
+        let open = unsafe { SDL_InitSubSystem(SDL_INIT_GAMECONTROLLER) == 0 };
+        if !open {
+            eprintln!("Could not start game controllers, continuing without them");
+        }
+        Self {
+            open,
+            players: (0..max_players).map(|_| None).collect(),
+            dead_zone: DEFAULT_DEAD_ZONE,
+        }
+    }
+
+    pub fn max_players(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_connected(&self, player: usize) -> bool {
+        self.players.get(player).is_some_and(|pad| pad.is_some())
+    }
+
+    /// Number of players with a controller
+    pub fn connected(&self) -> usize {
+        self.players.iter().filter(|pad| pad.is_some()).count()
+    }
+
+    /// Exchanges the controllers of two players, e.g. from a lobby screen
+    pub fn swap_players(&mut self, a: usize, b: usize) {
+        if a < self.players.len() && b < self.players.len() {
+            self.players.swap(a, b);
+        }
+    }
+
+    /// Checks for hot plugged controllers and returns everything that changed since the last poll
+    pub fn poll(&mut self) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+        if !self.open {
+            return events;
+        }
+        unsafe { SDL_GameControllerUpdate() };
+
+        for (player, slot) in self.players.iter_mut().enumerate() {
+            if slot.as_ref().is_some_and(
+                |pad| unsafe { SDL_GameControllerGetAttached(pad.controller) } != SDL_TRUE,
+            ) {
+                *slot = None;
+                events.push(GamepadEvent::Disconnected { player });
+            }
+        }
+        self.open_new(&mut events);
+
+        let dead_zone = self.dead_zone;
+        for (player, pad) in self
+            .players
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(player, pad)| pad.as_mut().map(|pad| (player, pad)))
+        {
+            let buttons = pad.buttons();
+            for (i, button) in GamepadButton::ALL.into_iter().enumerate() {
+                match (pad.buttons & 1 << i != 0, buttons & 1 << i != 0) {
+                    (false, true) => events.push(GamepadEvent::Pressed { player, button }),
+                    (true, false) => events.push(GamepadEvent::Released { player, button }),
+                    _ => (),
+                }
+            }
+            pad.buttons = buttons;
+
+            let dir = pad.direction(dead_zone);
+            if let Some(dir) = dir.filter(|dir| pad.dir != Some(*dir)) {
+                events.push(GamepadEvent::Direction { player, dir });
+            }
+            pad.dir = dir;
+        }
+        events
+    }
+
+    fn open_new(&mut self, events: &mut Vec<GamepadEvent>) {
+        for device in 0..unsafe { SDL_NumJoysticks() } {
+            let Some(player) = self.players.iter().position(|pad| pad.is_none()) else {
+                return;
+            };
+            let id = unsafe { SDL_JoystickGetDeviceInstanceID(device) };
+            if unsafe { SDL_IsGameController(device) } != SDL_TRUE
+                || self.players.iter().flatten().any(|pad| pad.id == id)
+            {
+                continue;
+            }
+            let controller = unsafe { SDL_GameControllerOpen(device) };
+            if controller.is_null() {
+                continue;
+            }
+            let id = unsafe { SDL_JoystickInstanceID(SDL_GameControllerGetJoystick(controller)) };
+            let mut pad = Gamepad {
+                controller,
+                id,
+                buttons: 0,
+                dir: None,
+            };
+            // Don't report buttons held while plugging in
+            pad.buttons = pad.buttons();
+            pad.dir = pad.direction(self.dead_zone);
+            self.players[player] = Some(pad);
+            events.push(GamepadEvent::Connected { player });
+        }
+    }
+}
+
+impl Drop for Gamepads {
+    fn drop(&mut self) {
+        if self.open {
+            self.players.clear();
+            unsafe { SDL_QuitSubSystem(SDL_INIT_GAMECONTROLLER) };
+        }
+    }
+}
@@ -0,0 +1,11 @@
+mod sdl_sys;
+
+pub mod args;
+pub mod bindings;
+pub mod gamepad;
+pub mod window;
+
+pub use args::Args;
+pub use bindings::{Action, Binding, Bindings, CONTROLS_FILE};
+pub use gamepad::{GamepadButton, GamepadEvent, Gamepads, PadDirection, DEFAULT_DEAD_ZONE};
+pub use window::Window;
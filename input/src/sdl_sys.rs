@@ -0,0 +1,78 @@
+//! The subset of SDL used for game controllers, key names and the window
+#![allow(non_camel_case_types)]
+
+use std::os::raw::{c_char, c_int};
+
+pub const SDL_INIT_GAMECONTROLLER: u32 = 0x00002000;
+pub const SDL_TRUE: c_int = 1;
+
+/// SDL_GameControllerButton
+pub const SDL_CONTROLLER_BUTTON_A: c_int = 0;
+pub const SDL_CONTROLLER_BUTTON_B: c_int = 1;
+pub const SDL_CONTROLLER_BUTTON_X: c_int = 2;
+pub const SDL_CONTROLLER_BUTTON_Y: c_int = 3;
+pub const SDL_CONTROLLER_BUTTON_BACK: c_int = 4;
+pub const SDL_CONTROLLER_BUTTON_START: c_int = 6;
+pub const SDL_CONTROLLER_BUTTON_LEFTSHOULDER: c_int = 9;
+pub const SDL_CONTROLLER_BUTTON_RIGHTSHOULDER: c_int = 10;
+pub const SDL_CONTROLLER_BUTTON_DPAD_UP: c_int = 11;
+pub const SDL_CONTROLLER_BUTTON_DPAD_DOWN: c_int = 12;
+pub const SDL_CONTROLLER_BUTTON_DPAD_LEFT: c_int = 13;
+pub const SDL_CONTROLLER_BUTTON_DPAD_RIGHT: c_int = 14;
+
+/// SDL_GameControllerAxis
+pub const SDL_CONTROLLER_AXIS_LEFTX: c_int = 0;
+pub const SDL_CONTROLLER_AXIS_LEFTY: c_int = 1;
+
+pub type SDL_JoystickID = i32;
+pub type SDL_Keycode = i32;
+
+/// Returned by `SDL_GetKeyFromName()` for unknown names
+pub const SDLK_UNKNOWN: SDL_Keycode = 0;
+
+#[repr(C)]
+pub struct SDL_GameController {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct SDL_Joystick {
+    _private: [u8; 0],
+}
+
+/// SDL_WindowFlags
+pub const SDL_WINDOW_FULLSCREEN: u32 = 0x00000001;
+pub const SDL_WINDOW_FULLSCREEN_DESKTOP: u32 = SDL_WINDOW_FULLSCREEN | 0x00001000;
+
+#[repr(C)]
+pub struct SDL_Window {
+    _private: [u8; 0],
+}
+
+#[link(name = "SDL2")]
+extern "C" {
+    pub fn SDL_InitSubSystem(flags: u32) -> c_int;
+    pub fn SDL_QuitSubSystem(flags: u32);
+    pub fn SDL_NumJoysticks() -> c_int;
+    pub fn SDL_IsGameController(joystick_index: c_int) -> c_int;
+    pub fn SDL_JoystickGetDeviceInstanceID(device_index: c_int) -> SDL_JoystickID;
+    pub fn SDL_GameControllerOpen(joystick_index: c_int) -> *mut SDL_GameController;
+    pub fn SDL_GameControllerClose(gamecontroller: *mut SDL_GameController);
+    pub fn SDL_GameControllerGetAttached(gamecontroller: *mut SDL_GameController) -> c_int;
+    pub fn SDL_GameControllerGetJoystick(
+        gamecontroller: *mut SDL_GameController,
+    ) -> *mut SDL_Joystick;
+    pub fn SDL_JoystickInstanceID(joystick: *mut SDL_Joystick) -> SDL_JoystickID;
+    pub fn SDL_GameControllerUpdate();
+    pub fn SDL_GameControllerGetButton(
+        gamecontroller: *mut SDL_GameController,
+        button: c_int,
+    ) -> u8;
+    pub fn SDL_GetKeyName(key: SDL_Keycode) -> *const c_char;
+    pub fn SDL_GetKeyFromName(name: *const c_char) -> SDL_Keycode;
+    pub fn SDL_GameControllerGetAxis(gamecontroller: *mut SDL_GameController, axis: c_int) -> i16;
+    pub fn SDL_GetWindowFromID(id: u32) -> *mut SDL_Window;
+    pub fn SDL_GetWindowSize(window: *mut SDL_Window, w: *mut c_int, h: *mut c_int);
+    pub fn SDL_GetWindowFlags(window: *mut SDL_Window) -> u32;
+    pub fn SDL_SetWindowFullscreen(window: *mut SDL_Window, flags: u32) -> c_int;
+}
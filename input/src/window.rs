@@ -0,0 +1,76 @@
+use std::os::raw::c_int;
+
+use crate::sdl_sys::*;
+
+/// The engine creates a single window, the first SDL gives an id
+const MAIN_WINDOW_ID: u32 = 1;
+
+/// The game window, polled for size changes since the engine consumes window events
+pub struct Window {
+    window: *mut SDL_Window,
+    size: (u32, u32),
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Self {
+            window: std::ptr::null_mut(),
+            size: (0, 0),
+        }
+    }
+
+    /// Looks the window up until it exists
+    fn window(&mut self) -> Option<*mut SDL_Window> {
+        if self.window.is_null() {
+            self.window = unsafe { SDL_GetWindowFromID(MAIN_WINDOW_ID) };
+        }
+        (!self.window.is_null()).then_some(self.window)
+    }
+
+    /// The window size in pixels, if the window exists
+    pub fn size(&self) -> Option<(u32, u32)> {
+        (self.size != (0, 0)).then_some(self.size)
+    }
+
+    /// Returns the new size if the window was created or resized since the last poll
+    pub fn poll(&mut self) -> Option<(u32, u32)> {
+        let window = self.window()?;
+        let (mut w, mut h): (c_int, c_int) = (0, 0);
+        unsafe { SDL_GetWindowSize(window, &mut w, &mut h) };
+        let size = (w.max(0) as u32, h.max(0) as u32);
+        if size == self.size {
+            return None;
+        }
+        self.size = size;
+        self.size()
+    }
+
+    pub fn is_fullscreen(&mut self) -> bool {
+        self.window().is_some_and(|window| {
+            let flags = unsafe { SDL_GetWindowFlags(window) };
+            flags & SDL_WINDOW_FULLSCREEN != 0
+        })
+    }
+
+    /// Uses desktop fullscreen so the display mode isn't changed
+    /// Returns whether the window is now fullscreen
+    pub fn set_fullscreen(&mut self, fullscreen: bool) -> bool {
+        let Some(window) = self.window() else {
+            return false;
+        };
+        let flags = match fullscreen {
+            true => SDL_WINDOW_FULLSCREEN_DESKTOP,
+            false => 0,
+        };
+        if unsafe { SDL_SetWindowFullscreen(window, flags) } != 0 {
+            eprintln!("Could not change fullscreen mode");
+        }
+        self.is_fullscreen()
+    }
+}
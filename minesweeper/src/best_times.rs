@@ -0,0 +1,33 @@
+use assets::settings::{read_key_values, save_file};
+
+use crate::Difficulty;
+
+pub const BEST_TIMES_FILE: &str = "save/minesweeper_best_times.txt";
+
+/// Formats milliseconds as seconds with one decimal place
+pub fn format_time(ms: u32) -> String {
+    format!("{}.{}", ms / 1000, ms % 1000 / 100)
+}
+
+/// Reads best times, one `<difficulty> <ms>` pair per line
+/// A missing or malformed file just means no best times yet
+pub fn load_best_times() -> [Option<u32>; 3] {
+    let mut times = [None; 3];
+    for (name, ms) in read_key_values(BEST_TIMES_FILE) {
+        if let (Some(d), Ok(ms)) = (
+            Difficulty::ALL.iter().find(|d| d.name() == name),
+            ms.parse(),
+        ) {
+            times[*d as usize] = Some(ms);
+        }
+    }
+    times
+}
+
+pub fn save_best_times(times: &[Option<u32>; 3]) {
+    let text = Difficulty::ALL
+        .iter()
+        .filter_map(|d| times[*d as usize].map(|ms| format!("{} {ms}\n", d.name())))
+        .collect::<String>();
+    save_file(BEST_TIMES_FILE, "best times", text);
+}
@@ -0,0 +1,349 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum CellState {
+    Hidden,
+    Flagged,
+    Revealed,
+}
+
+#[derive(Copy, Clone)]
+pub struct Cell {
+    pub mine: bool,
+    pub adjacent: u8,
+    pub state: CellState,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Reveal {
+    /// Nothing changed, e.g. the cell was flagged or already revealed
+    Nothing,
+    Safe,
+    Exploded,
+}
+
+/// Pure minesweeper rules, independent of rendering and input
+pub struct Minefield {
+    pub w: usize,
+    pub h: usize,
+    pub mines: usize,
+    pub seed: u64,
+    cells: Vec<Cell>,
+    generated: bool,
+    exploded: Option<(usize, usize)>,
+}
+
+impl Minefield {
+    pub fn new(w: usize, h: usize, mines: usize, seed: u64) -> Self {
+        Self {
+            w,
+            h,
+            mines: mines.min(w * h - 1),
+            seed,
+            cells: vec![
+                Cell {
+                    mine: false,
+                    adjacent: 0,
+                    state: CellState::Hidden,
+                };
+                w * h
+            ],
+            generated: false,
+            exploded: None,
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &Cell {
+        &self.cells[y * self.w + x]
+    }
+
+    fn get_mut(&mut self, x: usize, y: usize) -> &mut Cell {
+        &mut self.cells[y * self.w + x]
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.w && (y as usize) < self.h
+    }
+
+    pub fn neighbours(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|d| *d != (0, 0))
+            .map(move |(dx, dy)| (x as i32 + dx, y as i32 + dy))
+            .filter(|(x, y)| self.in_bounds(*x, *y))
+            .map(|(x, y)| (x as usize, y as usize))
+    }
+
+    /// Whether the first reveal has happened and mines have been placed
+    pub fn started(&self) -> bool {
+        self.generated
+    }
+
+    pub fn exploded(&self) -> Option<(usize, usize)> {
+        self.exploded
+    }
+
+    pub fn flags(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|cell| cell.state == CellState::Flagged)
+            .count()
+    }
+
+    pub fn won(&self) -> bool {
+        self.exploded.is_none()
+            && self
+                .cells
+                .iter()
+                .all(|cell| cell.mine || cell.state == CellState::Revealed)
+    }
+
+    /// Places mines from the seed, keeping the first clicked cell safe
+    /// Its neighbours are also kept safe when the board has room so the first click opens an area
+    fn generate(&mut self, x: usize, y: usize) {
+        let mut safe = vec![(x, y)];
+        if self.w * self.h - self.mines > 9 {
+            safe.extend(self.neighbours(x, y));
+        }
+        let mut candidates = (0..self.w * self.h)
+            .filter(|i| !safe.contains(&(i % self.w, i / self.w)))
+            .collect::<Vec<_>>();
+
+        // Partial Fisher-Yates shuffle, the first `mines` candidates become mines
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let n = self.mines.min(candidates.len());
+        for i in 0..n {
+            let j = rng.gen_range(i..candidates.len());
+            candidates.swap(i, j);
+            self.cells[candidates[i]].mine = true;
+        }
+        self.count_adjacent();
+        self.generated = true;
+    }
+
+    fn count_adjacent(&mut self) {
+        for y in 0..self.h {
+            for x in 0..self.w {
+                let adjacent = self
+                    .neighbours(x, y)
+                    .filter(|(x, y)| self.get(*x, *y).mine)
+                    .count() as u8;
+                self.get_mut(x, y).adjacent = adjacent;
+            }
+        }
+    }
+
+    /// Reveals a cell, flood filling through cells with no adjacent mines
+    pub fn reveal(&mut self, x: usize, y: usize) -> Reveal {
+        if self.exploded.is_some() || self.get(x, y).state != CellState::Hidden {
+            return Reveal::Nothing;
+        }
+        if !self.generated {
+            self.generate(x, y);
+        }
+        if self.get(x, y).mine {
+            self.get_mut(x, y).state = CellState::Revealed;
+            self.exploded = Some((x, y));
+            return Reveal::Exploded;
+        }
+
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            let cell = self.get_mut(x, y);
+            if cell.state != CellState::Hidden {
+                continue;
+            }
+            cell.state = CellState::Revealed;
+            if cell.adjacent == 0 {
+                stack.extend(
+                    self.neighbours(x, y)
+                        .filter(|(x, y)| self.get(*x, *y).state == CellState::Hidden),
+                );
+            }
+        }
+        Reveal::Safe
+    }
+
+    /// Reveals all unflagged neighbours of a revealed number once it has enough flags around it
+    pub fn chord(&mut self, x: usize, y: usize) -> Reveal {
+        let cell = *self.get(x, y);
+        if cell.state != CellState::Revealed || cell.adjacent == 0 {
+            return Reveal::Nothing;
+        }
+        let flags = self
+            .neighbours(x, y)
+            .filter(|(x, y)| self.get(*x, *y).state == CellState::Flagged)
+            .count();
+        if flags != cell.adjacent as usize {
+            return Reveal::Nothing;
+        }
+
+        let neighbours = self.neighbours(x, y).collect::<Vec<_>>();
+        let mut result = Reveal::Nothing;
+        for (x, y) in neighbours {
+            match self.reveal(x, y) {
+                Reveal::Exploded => return Reveal::Exploded,
+                Reveal::Safe => result = Reveal::Safe,
+                Reveal::Nothing => (),
+            }
+        }
+        result
+    }
+
+    /// Returns true if the cell changed
+    pub fn toggle_flag(&mut self, x: usize, y: usize) -> bool {
+        if self.exploded.is_some() {
+            return false;
+        }
+        let cell = self.get_mut(x, y);
+        cell.state = match cell.state {
+            CellState::Hidden => CellState::Flagged,
+            CellState::Flagged => CellState::Hidden,
+            CellState::Revealed => return false,
+        };
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A field with mines at `mines`, as if the first click had already placed them
+    fn field(w: usize, h: usize, mines: &[(usize, usize)]) -> Minefield {
+        let mut field = Minefield::new(w, h, mines.len(), 0);
+        for &(x, y) in mines {
+            field.get_mut(x, y).mine = true;
+        }
+        field.count_adjacent();
+        field.generated = true;
+        field
+    }
+
+    fn count(field: &Minefield, state: CellState) -> usize {
+        field
+            .cells
+            .iter()
+            .filter(|cell| cell.state == state)
+            .count()
+    }
+
+    fn mines(field: &Minefield) -> Vec<usize> {
+        (0..field.cells.len())
+            .filter(|i| field.cells[*i].mine)
+            .collect()
+    }
+
+    #[test]
+    fn first_click_and_neighbours_are_safe() {
+        for seed in 0..50 {
+            for (x, y) in [(0, 0), (4, 4), (8, 3)] {
+                let mut field = Minefield::new(9, 9, 10, seed);
+                assert!(!field.started());
+                assert!(field.reveal(x, y) == Reveal::Safe);
+                assert!(field.started());
+                assert_eq!(mines(&field).len(), 10);
+                assert!(!field.get(x, y).mine);
+                assert!(field.neighbours(x, y).all(|(x, y)| !field.get(x, y).mine));
+                // The click has no adjacent mines so it opens an area
+                assert!(count(&field, CellState::Revealed) > 1);
+            }
+        }
+    }
+
+    #[test]
+    fn crowded_first_click_is_safe() {
+        let mut field = Minefield::new(3, 3, 20, 7);
+        assert_eq!(field.mines, 8);
+        assert!(field.reveal(1, 1) == Reveal::Safe);
+        assert_eq!(mines(&field).len(), 8);
+        assert_eq!(field.get(1, 1).adjacent, 8);
+        assert!(field.won());
+    }
+
+    #[test]
+    fn adjacent_counts_match_mines() {
+        let mut field = Minefield::new(16, 16, 40, 3);
+        field.reveal(8, 8);
+        for y in 0..16 {
+            for x in 0..16 {
+                let n = field
+                    .neighbours(x, y)
+                    .filter(|(x, y)| field.get(*x, *y).mine);
+                assert_eq!(field.get(x, y).adjacent as usize, n.count());
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_mines() {
+        let mut a = Minefield::new(30, 16, 99, 42);
+        let mut b = Minefield::new(30, 16, 99, 42);
+        a.reveal(3, 5);
+        b.reveal(3, 5);
+        assert_eq!(mines(&a), mines(&b));
+    }
+
+    #[test]
+    fn reveal_cascades_to_numbers() {
+        // A column of mines splits the field
+        let mut field = field(5, 3, &[(2, 0), (2, 1), (2, 2)]);
+        assert!(field.reveal(0, 1) == Reveal::Safe);
+        for y in 0..3 {
+            assert!(field.get(0, y).state == CellState::Revealed);
+            assert!(field.get(1, y).state == CellState::Revealed);
+            assert!(field.get(3, y).state == CellState::Hidden);
+            assert!(field.get(4, y).state == CellState::Hidden);
+        }
+        assert_eq!(count(&field, CellState::Revealed), 6);
+        assert!(!field.won());
+    }
+
+    #[test]
+    fn reveal_cascade_skips_flags() {
+        let mut field = field(5, 5, &[(4, 4)]);
+        assert!(field.toggle_flag(0, 0));
+        assert!(field.reveal(0, 4) == Reveal::Safe);
+        assert!(field.get(0, 0).state == CellState::Flagged);
+        assert_eq!(count(&field, CellState::Revealed), 23);
+        assert!(!field.won());
+
+        assert!(field.reveal(0, 0) == Reveal::Nothing);
+        assert!(field.toggle_flag(0, 0));
+        assert!(field.reveal(0, 0) == Reveal::Safe);
+        assert!(field.won());
+    }
+
+    #[test]
+    fn reveal_mine_explodes() {
+        let mut field = field(3, 3, &[(0, 0)]);
+        assert!(field.reveal(0, 0) == Reveal::Exploded);
+        assert_eq!(field.exploded(), Some((0, 0)));
+        assert!(field.reveal(2, 2) == Reveal::Nothing);
+        assert!(!field.toggle_flag(2, 2));
+        assert!(!field.won());
+    }
+
+    #[test]
+    fn chord_needs_matching_flags() {
+        let mut field = field(3, 3, &[(0, 0)]);
+        assert!(field.chord(1, 1) == Reveal::Nothing);
+        assert!(field.reveal(1, 1) == Reveal::Safe);
+        assert_eq!(count(&field, CellState::Revealed), 1);
+        assert!(field.chord(1, 1) == Reveal::Nothing);
+
+        field.toggle_flag(0, 0);
+        assert!(field.chord(1, 1) == Reveal::Safe);
+        assert_eq!(count(&field, CellState::Revealed), 8);
+        assert!(field.won());
+    }
+
+    #[test]
+    fn chord_with_wrong_flag_explodes() {
+        let mut field = field(3, 3, &[(0, 0)]);
+        field.reveal(1, 1);
+        field.toggle_flag(2, 2);
+        assert!(field.chord(1, 1) == Reveal::Exploded);
+        assert_eq!(field.exploded(), Some((0, 0)));
+    }
+}
@@ -0,0 +1,88 @@
+use hyperfold_engine::{
+    _engine::Entity, add_components, components, ecs::entities::NewEntity, sdl2::SDL_KeyCode,
+};
+
+use ::input::{Action, Binding, Bindings};
+
+use crate::{_engine::Components, Difficulty, Playing};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum MinesweeperAction {
+    /// Same difficulty
+    Restart,
+    /// Restarts at this difficulty
+    Size(Difficulty),
+}
+
+impl Action for MinesweeperAction {
+    const GAME: &'static str = "minesweeper";
+    const ALL: &'static [Self] = &[
+        MinesweeperAction::Restart,
+        MinesweeperAction::Size(Difficulty::Beginner),
+        MinesweeperAction::Size(Difficulty::Intermediate),
+        MinesweeperAction::Size(Difficulty::Expert),
+    ];
+
+    fn id(&self) -> &'static str {
+        match self {
+            MinesweeperAction::Restart => "restart",
+            MinesweeperAction::Size(difficulty) => difficulty.name(),
+        }
+    }
+
+    fn defaults(&self) -> Vec<Binding> {
+        let key = match self {
+            MinesweeperAction::Restart => SDL_KeyCode::SDLK_r,
+            MinesweeperAction::Size(Difficulty::Beginner) => SDL_KeyCode::SDLK_1,
+            MinesweeperAction::Size(Difficulty::Intermediate) => SDL_KeyCode::SDLK_2,
+            MinesweeperAction::Size(Difficulty::Expert) => SDL_KeyCode::SDLK_3,
+        };
+        vec![Binding::Key(key as i32)]
+    }
+}
+
+/// Bindings from the `[minesweeper]` section of the controls file
+#[hyperfold_engine::component(Singleton)]
+struct Controls {
+    pub bindings: Bindings<MinesweeperAction>,
+    /// Set once this game starts, the games launch from the same binary and share key events
+    pub active: bool,
+}
+
+impl Controls {
+    /// The action an SDL key triggers, none before this game starts
+    pub fn action(&self, key: SDL_KeyCode) -> Option<MinesweeperAction> {
+        self.bindings
+            .action(Binding::Key(key as i32))
+            .filter(|_| self.active)
+    }
+
+    /// The size keys, e.g. "1/2/3"
+    pub fn size_hint(&self) -> String {
+        Difficulty::ALL
+            .iter()
+            .map(|d| self.bindings.hint(MinesweeperAction::Size(*d)))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_controls(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Controls {
+            bindings: Bindings::load(),
+            active: false
+        }
+    );
+}
+
+components!(ControlsCS, controls: &'a mut Controls);
+
+#[hyperfold_engine::system]
+fn activate_controls(_: &Playing::OnEnter, ControlsCS { controls, .. }: ControlsCS) {
+    controls.active = true;
+}
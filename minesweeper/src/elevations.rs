@@ -0,0 +1,6 @@
+#[repr(u8)]
+pub enum Elevations {
+    Board = 0,
+    Hud,
+    GameOverScreen,
+}
@@ -0,0 +1,223 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::{
+        physics::Position,
+        render_system::{
+            drawable::Canvas,
+            font::{FontData, TIMES},
+            render_data::{Fit, RenderDataBuilderTrait, RenderTexture},
+            render_text::RenderText,
+            shapes::{Rectangle, ShapeTrait},
+            AssetManager, Camera, Elevation, RenderComponent, Renderer, Texture,
+        },
+    },
+    sdl2::SDL_Color,
+    utils::{
+        colors::{gray, BLACK},
+        rect::{Align, Rect},
+    },
+};
+
+use crate::{
+    _engine::Components,
+    board::{CellState, Minefield},
+    elevations::Elevations,
+    Playing, Settings, SettingsCS, CELL_F, CELL_W,
+};
+
+const NUMBER_COLORS: [SDL_Color; 8] = [
+    SDL_Color {
+        r: 0,
+        g: 0,
+        b: 255,
+        a: 255,
+    },
+    SDL_Color {
+        r: 0,
+        g: 128,
+        b: 0,
+        a: 255,
+    },
+    SDL_Color {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    },
+    SDL_Color {
+        r: 0,
+        g: 0,
+        b: 128,
+        a: 255,
+    },
+    SDL_Color {
+        r: 128,
+        g: 0,
+        b: 0,
+        a: 255,
+    },
+    SDL_Color {
+        r: 0,
+        g: 128,
+        b: 128,
+        a: 255,
+    },
+    SDL_Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    },
+    SDL_Color {
+        r: 128,
+        g: 128,
+        b: 128,
+        a: 255,
+    },
+];
+
+const FLAG_COLOR: SDL_Color = SDL_Color {
+    r: 220,
+    g: 40,
+    b: 40,
+    a: 255,
+};
+
+#[hyperfold_engine::component(Singleton)]
+struct Field {
+    pub field: Minefield,
+    /// Milliseconds since the first click
+    pub time: u32,
+    /// Set when the board texture needs to be baked again
+    pub dirty: bool,
+}
+
+impl Field {
+    pub fn over(&self) -> bool {
+        self.field.exploded().is_some() || self.field.won()
+    }
+}
+
+components!(
+    FieldCS,
+    field: &'a mut Field,
+    pos: &'a mut Position,
+    tex: &'a mut RenderComponent
+);
+
+pub fn reset_field(field: &mut Field, pos: &mut Position, settings: &Settings) {
+    field.field = settings.difficulty.new_field(settings.next_seed());
+    field.time = 0;
+    field.dirty = true;
+    pos.0 = settings.difficulty.rect();
+}
+
+#[hyperfold_engine::system]
+fn new_field(
+    _: &Playing::OnEnter,
+    fields: Vec<FieldCS>,
+    SettingsCS { settings, .. }: SettingsCS,
+    entities: &mut dyn Components,
+    camera: &mut Camera,
+) {
+    camera.0.set_pos(0.0, 0.0, Align::Center, Align::Center);
+
+    // The field stays visible on the game over screen, so it is reused between games
+    if let Some(field) = fields.into_iter().next() {
+        reset_field(field.field, field.pos, settings);
+        return;
+    }
+
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Field {
+            field: settings.difficulty.new_field(settings.next_seed()),
+            time: 0,
+            dirty: true
+        },
+        Elevation(Elevations::Board as u8),
+        RenderComponent::new(RenderTexture::new(None)),
+        Position(settings.difficulty.rect())
+    );
+}
+
+#[hyperfold_engine::system]
+fn tick_field(update: &Update, field: FieldCS) {
+    if field.field.field.started() && !field.field.over() {
+        field.field.time += update.0;
+    }
+}
+
+fn draw_cell(
+    field: &Minefield,
+    x: usize,
+    y: usize,
+    tex: &Texture,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let rect = Rect {
+        x: x as f32 * CELL_F,
+        y: y as f32 * CELL_F,
+        w: CELL_F,
+        h: CELL_F,
+    };
+    let cell = field.get(x, y);
+    let bkgrnd = match cell.state {
+        CellState::Revealed if field.exploded() == Some((x, y)) => FLAG_COLOR,
+        CellState::Revealed => gray(160),
+        _ => gray(200),
+    };
+    tex.draw(r, &mut Rectangle::new().set_color(bkgrnd).fill(rect));
+    tex.draw(
+        r,
+        &mut Rectangle::new()
+            .set_color(gray(120))
+            .border(rect, -1.0, false),
+    );
+
+    // Show every mine once the game is lost
+    let show_mine = cell.mine && (cell.state == CellState::Revealed || field.exploded().is_some());
+    let inner = Rect::from_center(rect.cx(), rect.cy(), CELL_F / 2.0, CELL_F / 2.0);
+    if cell.state == CellState::Flagged && !show_mine {
+        tex.draw(r, &mut Rectangle::new().set_color(FLAG_COLOR).fill(inner));
+    } else if show_mine {
+        tex.draw(r, &mut Rectangle::new().set_color(BLACK).fill(inner));
+    } else if cell.state == CellState::Revealed && cell.adjacent > 0 {
+        let center = Rect::from_center(rect.cx(), rect.cy(), 0.0, 0.0);
+        let mut rt = RenderText::new(FontData {
+            w: None,
+            h: Some(CELL_W * 3 / 4),
+            sample: "8".to_string(),
+            file: TIMES.to_string(),
+        })
+        .with_text(&cell.adjacent.to_string())
+        .with_text_color(NUMBER_COLORS[cell.adjacent as usize - 1])
+        .with_dest_align(Align::Center, Align::Center)
+        .with_dest_fit(Fit::None)
+        .with_dest_rect(center);
+        rt.render_text(center, r, am);
+        tex.draw(r, &mut rt);
+    }
+}
+
+#[hyperfold_engine::system]
+fn draw_field(_: &Update, field: FieldCS, r: &Renderer, am: &mut AssetManager) {
+    if !field.field.dirty {
+        return;
+    }
+    field.field.dirty = false;
+
+    let f = &field.field.field;
+    let tex = Texture::new(r, f.w as u32 * CELL_W, f.h as u32 * CELL_W, gray(200));
+    for y in 0..f.h {
+        for x in 0..f.w {
+            draw_cell(f, x, y, &tex, r, am);
+        }
+    }
+    *field.tex = RenderComponent::new(RenderTexture::new(Some(tex)));
+}
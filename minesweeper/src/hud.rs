@@ -0,0 +1,104 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::{
+        physics::Position,
+        render_system::{
+            font::{FontData, TIMES},
+            render_data::{Fit, RenderDataBuilderTrait},
+            render_text::RenderText,
+            AssetManager, Elevation, RenderComponent, Renderer,
+        },
+    },
+    utils::{
+        colors::WHITE,
+        rect::{Align, Rect},
+    },
+};
+
+use assets::strings::tr_with;
+
+use crate::{_engine::Components, elevations::Elevations, field::FieldCS, Playing, CELL_F, CELL_W};
+
+/// Shows the remaining mine count and the elapsed time above the field
+#[hyperfold_engine::component(Singleton)]
+struct Hud {
+    pub mines_left: i64,
+    pub secs: u32,
+}
+
+components!(HudCS, hud: &'a mut Hud, pos: &'a mut Position, tex: &'a mut RenderComponent);
+
+#[hyperfold_engine::system]
+fn new_hud(_: &Playing::OnEnter, huds: Vec<HudCS>, entities: &mut dyn Components) {
+    if let Some(hud) = huds.into_iter().next() {
+        // Force a redraw
+        hud.hud.secs = u32::MAX;
+        return;
+    }
+
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Hud {
+            mines_left: 0,
+            secs: u32::MAX
+        },
+        Elevation(Elevations::Hud as u8),
+        RenderComponent::new(RenderText::new(FontData {
+            w: None,
+            h: Some(CELL_W * 2 / 3),
+            sample: hud_sample(),
+            file: TIMES.to_string(),
+        })),
+        Position(Rect::from_center(0.0, 0.0, 0.0, 0.0))
+    );
+}
+
+/// Sizes the text for the widest counts
+fn hud_sample() -> String {
+    tr_with("minesweeper_hud", &[("mines", "999"), ("time", "999")])
+}
+
+#[hyperfold_engine::system]
+fn update_hud(_: &Update, hud: HudCS, field: FieldCS, r: &Renderer, am: &mut AssetManager) {
+    let f = &field.field.field;
+    let mines_left = f.mines as i64 - f.flags() as i64;
+    let secs = field.field.time / 1000;
+    if hud.hud.mines_left == mines_left && hud.hud.secs == secs {
+        return;
+    }
+    hud.hud.mines_left = mines_left;
+    hud.hud.secs = secs;
+
+    let rect = Rect::from(
+        field.pos.0.cx(),
+        field.pos.0.y - CELL_F / 4.0,
+        0.0,
+        0.0,
+        Align::Center,
+        Align::BotRight,
+    );
+    hud.pos.0 = rect;
+    let mut rt = RenderText::new(FontData {
+        w: None,
+        h: Some(CELL_W * 2 / 3),
+        sample: hud_sample(),
+        file: TIMES.to_string(),
+    })
+    .with_text(&tr_with(
+        "minesweeper_hud",
+        &[
+            ("mines", &mines_left.to_string()),
+            ("time", &secs.to_string()),
+        ],
+    ))
+    .with_text_color(WHITE)
+    .with_dest_align(Align::Center, Align::BotRight)
+    .with_dest_fit(Fit::None)
+    .with_dest_rect(rect);
+    rt.render_text(rect, r, am);
+    *hud.tex = RenderComponent::new(rt);
+}
@@ -0,0 +1,63 @@
+use hyperfold_engine::{
+    framework::{event_system::events::Mouse, render_system::Camera},
+    sdl2::{SDL_BUTTON_LEFT, SDL_BUTTON_MIDDLE, SDL_BUTTON_RIGHT},
+};
+
+use crate::{
+    _engine::Events,
+    best_times::save_best_times,
+    board::{CellState, Reveal},
+    field::FieldCS,
+    screen_to_world, GameOver, SettingsCS, CELL_F,
+};
+
+#[hyperfold_engine::system]
+fn click_field(
+    mouse: &Mouse,
+    field: FieldCS,
+    SettingsCS { settings, .. }: SettingsCS,
+    camera: &Camera,
+    events: &mut dyn Events,
+) {
+    if !mouse.0.released() || field.field.over() {
+        return;
+    }
+
+    let pos = screen_to_world(mouse.0.click_pos, camera);
+    let (x, y) = (
+        ((pos.x - field.pos.0.x) / CELL_F).floor() as i32,
+        ((pos.y - field.pos.0.y) / CELL_F).floor() as i32,
+    );
+    let f = &mut field.field.field;
+    if !f.in_bounds(x, y) {
+        return;
+    }
+    let (x, y) = (x as usize, y as usize);
+
+    let changed = match mouse.0.mouse as u32 {
+        // Clicking a revealed number chords it
+        SDL_BUTTON_LEFT => match f.get(x, y).state {
+            CellState::Revealed => f.chord(x, y) != Reveal::Nothing,
+            _ => f.reveal(x, y) != Reveal::Nothing,
+        },
+        SDL_BUTTON_MIDDLE => f.chord(x, y) != Reveal::Nothing,
+        SDL_BUTTON_RIGHT => f.toggle_flag(x, y),
+        _ => false,
+    };
+    if !changed {
+        return;
+    }
+    field.field.dirty = true;
+
+    if f.won() {
+        let time = field.field.time;
+        let best = &mut settings.best_times[settings.difficulty as usize];
+        if best.map_or(true, |best| time < best) {
+            *best = Some(time);
+            save_best_times(&settings.best_times);
+        }
+    }
+    if field.field.over() {
+        events.set_state(GameOver::Data);
+    }
+}
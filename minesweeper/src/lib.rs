@@ -0,0 +1,234 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::entities::NewEntity,
+    framework::{
+        event_system::events::Key,
+        physics::Position,
+        render_system::{
+            font::TIMES, render_data::RenderTexture, AssetManager, Camera, Elevation,
+            RenderComponent, Renderer,
+        },
+    },
+    sdl2::SDL_Color,
+    utils::{
+        rand::{new_rng, Rng},
+        rect::{Point, PointF, Rect},
+    },
+};
+
+use ::input::Args;
+use assets::strings::{tr, tr_with};
+use ui::{GameOverText, OverlayLine};
+
+use crate::{
+    best_times::{format_time, load_best_times},
+    board::Minefield,
+    controls::{ControlsCS, MinesweeperAction},
+    elevations::Elevations,
+    field::{reset_field, FieldCS},
+};
+
+pub mod best_times;
+pub mod board;
+pub mod controls;
+pub mod elevations;
+pub mod field;
+pub mod hud;
+pub mod input;
+
+use hyperfold_engine::system_macro;
+
+hyperfold_engine::game_crate!();
+
+pub const CELL_W: u32 = 30;
+pub const CELL_F: f32 = CELL_W as f32;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [
+        Difficulty::Beginner,
+        Difficulty::Intermediate,
+        Difficulty::Expert,
+    ];
+
+    /// Returns (width, height, mines)
+    pub fn size(&self) -> (usize, usize, usize) {
+        match self {
+            Difficulty::Beginner => (9, 9, 10),
+            Difficulty::Intermediate => (16, 16, 40),
+            Difficulty::Expert => (30, 16, 99),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Intermediate => "intermediate",
+            Difficulty::Expert => "expert",
+        }
+    }
+
+    pub fn new_field(&self, seed: u64) -> Minefield {
+        let (w, h, mines) = self.size();
+        Minefield::new(w, h, mines, seed)
+    }
+
+    pub fn rect(&self) -> Rect {
+        let (w, h, _) = self.size();
+        Rect::from_center(0.0, 0.0, w as f32 * CELL_F, h as f32 * CELL_F)
+    }
+}
+
+/// Converts a point on the screen to world coordinates
+pub fn screen_to_world(pos: Point, camera: &Camera) -> PointF {
+    PointF {
+        x: camera.0.x + pos.x as f32,
+        y: camera.0.y + pos.y as f32,
+    }
+}
+
+/// Persists between games
+#[hyperfold_engine::component(Singleton)]
+struct Settings {
+    pub difficulty: Difficulty,
+    pub seed: Option<u64>,
+    pub best_times: [Option<u32>; 3],
+}
+
+impl Settings {
+    pub fn next_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| new_rng().gen())
+    }
+
+    pub fn best_time(&self) -> Option<u32> {
+        self.best_times[self.difficulty as usize]
+    }
+}
+
+components!(SettingsCS, settings: &'a mut Settings);
+
+#[hyperfold_engine::system(Init)]
+fn new_settings(entities: &mut dyn _engine::Components) {
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Settings {
+            difficulty: Difficulty::Beginner,
+            seed: Args::get().value("seed"),
+            best_times: load_best_times(),
+        }
+    );
+}
+
+#[hyperfold_engine::state]
+struct Playing;
+
+#[hyperfold_engine::state]
+struct GameOver;
+
+#[hyperfold_engine::component(Singleton)]
+struct GameOverScreen;
+
+components!(labels(GameOverScreen), GameOverEids);
+
+#[hyperfold_engine::system]
+fn game_over(
+    _: &GameOver::OnEnter,
+    field: FieldCS,
+    SettingsCS { settings, .. }: SettingsCS,
+    ControlsCS { controls, .. }: ControlsCS,
+    entities: &mut dyn _engine::Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let rect = settings.difficulty.rect();
+    let (w, h) = (rect.w as u32, rect.h as u32);
+    let won = field.field.field.won();
+    let title = tr(if won { "you_win" } else { "game_over" });
+    let game_over = tr("game_over");
+    let elapsed = format_time(field.field.time);
+    let time = match (won, settings.best_time()) {
+        (true, Some(best)) => tr_with(
+            "minesweeper_time_best",
+            &[("time", &elapsed), ("best", &format_time(best))],
+        ),
+        _ => tr_with("minesweeper_time", &[("time", &elapsed)]),
+    };
+    let time_sample = tr_with(
+        "minesweeper_time_best",
+        &[("time", "000.0"), ("best", "000.0")],
+    );
+    let hint = tr_with(
+        "minesweeper_restart_hint",
+        &[
+            (
+                "restart",
+                &controls.bindings.hint(MinesweeperAction::Restart),
+            ),
+            ("sizes", &controls.size_hint()),
+        ],
+    );
+    let tex = GameOverText {
+        title: OverlayLine::new(&title, w / 3).with_sample(&game_over),
+        lines: vec![
+            OverlayLine::new(&time, w / 2).with_sample(&time_sample),
+            OverlayLine::new(&hint, w * 2 / 3),
+        ],
+        font: TIMES.to_string(),
+        overlay: SDL_Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 96,
+        },
+    }
+    .texture(w, h, r, am);
+
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        GameOverScreen,
+        GameOver::Label,
+        Elevation(Elevations::GameOverScreen as u8),
+        RenderComponent::new(RenderTexture::new(Some(tex))),
+        Position(rect)
+    );
+}
+
+#[hyperfold_engine::system]
+fn restart(
+    key: &Key,
+    game_over: Vec<GameOverEids>,
+    SettingsCS { settings, .. }: SettingsCS,
+    ControlsCS { controls, .. }: ControlsCS,
+    field: FieldCS,
+    events: &mut dyn _engine::Events,
+) {
+    if !key.0.pressed() {
+        return;
+    }
+
+    let difficulty = match controls.action(key.0.key) {
+        Some(MinesweeperAction::Restart) => settings.difficulty,
+        Some(MinesweeperAction::Size(difficulty)) => difficulty,
+        None => return,
+    };
+
+    if !game_over.is_empty() {
+        settings.difficulty = difficulty;
+        events.set_state(Playing::Data);
+    } else if !field.field.field.started() && difficulty != settings.difficulty {
+        // Nothing to lose before the first click, swap boards immediately
+        settings.difficulty = difficulty;
+        reset_field(field.field, field.pos, settings);
+    }
+}
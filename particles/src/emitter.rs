@@ -0,0 +1,195 @@
+use hyperfold_engine::{
+    sdl2::SDL_Color,
+    utils::rect::{PointF, Rect},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Colors are blended in this many steps over a particle's life,
+/// each step is one texture
+pub const COLOR_STEPS: u8 = 8;
+
+#[derive(Clone)]
+pub struct EmitterConfig {
+    /// Particles per second while emitting, bursts ignore this
+    pub rate: f32,
+    pub lifetime_ms: (u32, u32),
+    /// Pixels per second
+    pub speed: (f32, f32),
+    /// Direction in degrees, 0 is right and 90 is down
+    pub angle: f32,
+    /// Degrees either side of `angle`
+    pub spread: f32,
+    /// Pixels per second squared
+    pub gravity: PointF,
+    /// Size at birth and death
+    pub size: (f32, f32),
+    /// Color at birth and death, including alpha
+    pub color: (SDL_Color, SDL_Color),
+    /// Image drawn for each particle, a square if None
+    pub texture: Option<String>,
+}
+
+impl Default for EmitterConfig {
+    fn default() -> Self {
+        let white = SDL_Color {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        };
+        Self {
+            rate: 0.0,
+            lifetime_ms: (500, 500),
+            speed: (50.0, 100.0),
+            angle: 0.0,
+            spread: 180.0,
+            gravity: PointF::new(),
+            size: (8.0, 8.0),
+            color: (white, SDL_Color { a: 0, ..white }),
+            texture: None,
+        }
+    }
+}
+
+impl EmitterConfig {
+    /// Color for a step in `0..COLOR_STEPS`
+    pub fn step_color(&self, step: u8) -> SDL_Color {
+        let t = step as f32 / (COLOR_STEPS - 1) as f32;
+        let (c1, c2) = self.color;
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        SDL_Color {
+            r: lerp(c1.r, c2.r),
+            g: lerp(c1.g, c2.g),
+            b: lerp(c1.b, c2.b),
+            a: lerp(c1.a, c2.a),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Particle {
+    pub pos: PointF,
+    pub v: PointF,
+    pub age: u32,
+    pub lifetime: u32,
+}
+
+impl Particle {
+    /// Fraction of its life that has passed
+    pub fn t(&self) -> f32 {
+        (self.age as f32 / self.lifetime.max(1) as f32).min(1.0)
+    }
+
+    pub fn color_step(&self) -> u8 {
+        (self.t() * (COLOR_STEPS - 1) as f32).round() as u8
+    }
+
+    pub fn rect(&self, config: &EmitterConfig) -> Rect {
+        let (s1, s2) = config.size;
+        let size = s1 + (s2 - s1) * self.t();
+        Rect::from_center(self.pos.x, self.pos.y, size, size)
+    }
+}
+
+/// A fixed number of particle slots that are reused, so bursts never allocate
+/// When every slot is alive the oldest slot is recycled
+pub struct ParticlePool {
+    particles: Vec<Option<Particle>>,
+    next: usize,
+    /// Fractional particles carried between frames while emitting
+    pending: f32,
+    rng: StdRng,
+}
+
+impl ParticlePool {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            particles: vec![None; capacity.max(1)],
+            next: 0,
+            pending: 0.0,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// The particle in slot `i`, None if the slot is free
+    pub fn get(&self, i: usize) -> Option<&Particle> {
+        self.particles.get(i).and_then(|p| p.as_ref())
+    }
+
+    pub fn alive(&self) -> usize {
+        self.particles.iter().filter(|p| p.is_some()).count()
+    }
+
+    fn spawn(&mut self, origin: PointF, config: &EmitterConfig) {
+        let n = self.particles.len();
+        let i = (0..n)
+            .map(|j| (self.next + j) % n)
+            .find(|i| self.particles[*i].is_none())
+            .unwrap_or(self.next);
+        self.next = (i + 1) % n;
+
+        let rand = |rng: &mut StdRng, (min, max): (f32, f32)| match min < max {
+            true => rng.gen_range(min..max),
+            false => min,
+        };
+        let angle = rand(
+            &mut self.rng,
+            (config.angle - config.spread, config.angle + config.spread),
+        )
+        .to_radians();
+        let speed = rand(&mut self.rng, config.speed);
+        let (l1, l2) = config.lifetime_ms;
+        let lifetime = match l1 < l2 {
+            true => self.rng.gen_range(l1..l2),
+            false => l1,
+        };
+        self.particles[i] = Some(Particle {
+            pos: origin,
+            v: PointF {
+                x: speed * angle.cos(),
+                y: speed * angle.sin(),
+            },
+            age: 0,
+            lifetime,
+        });
+    }
+
+    /// Spawns `n` particles at once
+    pub fn burst(&mut self, n: usize, origin: PointF, config: &EmitterConfig) {
+        for _ in 0..n {
+            self.spawn(origin, config);
+        }
+    }
+
+    /// Spawns particles at `config.rate` for `ms` milliseconds
+    pub fn emit(&mut self, ms: u32, origin: PointF, config: &EmitterConfig) {
+        self.pending += config.rate * ms as f32 / 1000.0;
+        while self.pending >= 1.0 {
+            self.pending -= 1.0;
+            self.spawn(origin, config);
+        }
+    }
+
+    /// Ages and moves every particle, freeing slots whose particle has died
+    pub fn update(&mut self, ms: u32, config: &EmitterConfig) {
+        let dt = ms as f32 / 1000.0;
+        for slot in self.particles.iter_mut() {
+            let Some(p) = slot else {
+                continue;
+            };
+            p.age += ms;
+            if p.age >= p.lifetime {
+                *slot = None;
+                continue;
+            }
+            p.v.x += config.gravity.x * dt;
+            p.v.y += config.gravity.y * dt;
+            p.pos.x += p.v.x * dt;
+            p.pos.y += p.v.y * dt;
+        }
+    }
+}
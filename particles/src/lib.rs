@@ -0,0 +1,5 @@
+pub mod emitter;
+pub mod texture;
+
+pub use emitter::{EmitterConfig, Particle, ParticlePool, COLOR_STEPS};
+pub use texture::particle_texture;
@@ -0,0 +1,49 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    path::Path,
+};
+
+use hyperfold_engine::sdl2::SDL_Color;
+use image::{ImageError, Rgba, RgbaImage};
+
+/// Size of the square drawn when a particle has no texture
+pub const SQUARE_W: u32 = 8;
+
+/// Returns an image file of `texture` tinted by `color`, ignoring alpha
+/// Files are shared through the asset manager, so recoloring a particle just swaps files
+pub fn particle_texture(texture: Option<&str>, color: SDL_Color) -> String {
+    let mut hasher = DefaultHasher::new();
+    texture.hash(&mut hasher);
+    (color.r, color.g, color.b).hash(&mut hasher);
+    let file = std::env::temp_dir()
+        .join("hyperfold_particles")
+        .join(format!("{:016x}.png", hasher.finish()));
+    if file.is_file() {
+        return file.to_string_lossy().to_string();
+    }
+
+    let res = match texture {
+        Some(texture) => image::open(texture).map(|img| img.into_rgba8()),
+        None => Ok(RgbaImage::from_pixel(
+            SQUARE_W,
+            SQUARE_W,
+            Rgba([255, 255, 255, 255]),
+        )),
+    }
+    .and_then(|mut img| {
+        img.pixels_mut().for_each(|p| {
+            p.0[0] = (p.0[0] as u16 * color.r as u16 / 255) as u8;
+            p.0[1] = (p.0[1] as u16 * color.g as u16 / 255) as u8;
+            p.0[2] = (p.0[2] as u16 * color.b as u16 / 255) as u8;
+        });
+        fs::create_dir_all(file.parent().unwrap_or(Path::new(".")))
+            .map_err(ImageError::IoError)
+            .and_then(|_| img.save(&file))
+    });
+    if let Err(e) = res {
+        panic!("Could not write {}: {e}", file.display());
+    }
+    file.to_string_lossy().to_string()
+}
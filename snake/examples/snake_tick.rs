@@ -0,0 +1,120 @@
+//! Runs the snake tick for a 10,000 segment snake and times it against the frame budget:
+//! stepping the trail, moving every hit box in the collision hash and building the body path
+//! Run from `snake/` with `cargo run --release --example snake_tick`
+
+use std::time::{Duration, Instant};
+
+use grid::SpatialHash;
+use hyperfold_engine::utils::rect::{PointF, Rect};
+
+use snake::{
+    body_renderer::path_pieces,
+    fixed_step::DEFAULT_TICK_HZ,
+    snake::{Direction, SNAKE_SPEED},
+    snake_body::{step_trail, SNAKE_HB_W, SNAKE_W},
+    trail::Trail,
+};
+
+const SEGMENTS: usize = 10_000;
+/// Segments per row of the coiled snake
+const ROW_LEN: usize = 100;
+const TICKS: usize = 1_000;
+const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+/// Ticks in a 60 frames per second frame
+const TICKS_PER_FRAME: u32 = DEFAULT_TICK_HZ / 60;
+/// Straight pieces are split at the length of the default body strip, 12 rows
+const MAX_PIECE_LEN: f32 = SNAKE_W * 11.0;
+
+/// A snake coiled back and forth in rows, with enough trail behind its head for every segment
+fn coiled_trail() -> Trail {
+    let mut trail = Trail::new(PointF { x: 0.0, y: 0.0 });
+    for row in 0..SEGMENTS.div_ceil(ROW_LEN) + 1 {
+        trail.turn(match row % 2 {
+            0 => Direction::Right,
+            _ => Direction::Left,
+        });
+        trail.advance(ROW_LEN as f32 * SNAKE_W);
+        trail.turn(Direction::Down);
+        trail.advance(SNAKE_W);
+    }
+    trail.turn(Direction::Right);
+    trail
+}
+
+fn hit_box(pos: PointF) -> Rect {
+    Rect::from_center(pos.x, pos.y, SNAKE_HB_W, SNAKE_HB_W)
+}
+
+/// Time spent in each part of the tick
+#[derive(Default)]
+struct Timings {
+    trail: Duration,
+    collisions: Duration,
+    render: Duration,
+}
+
+impl Timings {
+    fn total(&self) -> Duration {
+        self.trail + self.collisions + self.render
+    }
+}
+
+fn main() {
+    let mut trail = coiled_trail();
+    let mut hash = SpatialHash::new(SNAKE_W);
+    for (i, (pos, _)) in trail.sample(SNAKE_W, SEGMENTS).into_iter().enumerate() {
+        hash.insert(i, hit_box(pos));
+    }
+
+    let dist = SNAKE_SPEED / DEFAULT_TICK_HZ as f32;
+    let (mut total, mut worst) = (Timings::default(), Duration::ZERO);
+    let (mut hits, mut pieces) = (0, 0);
+    for _ in 0..TICKS {
+        let start = Instant::now();
+        // `update_snake_bodies`
+        let samples = step_trail(&mut trail, dist, SEGMENTS);
+        let stepped = Instant::now();
+        for (i, (pos, _)) in samples.iter().enumerate() {
+            hash.insert(i, hit_box(*pos));
+        }
+        // `collide_snake`
+        hits += hash
+            .query(&hit_box(samples[0].0))
+            .filter(|i| *i != 0)
+            .count();
+        let collided = Instant::now();
+        // `render_snake_body`
+        let mut points = vec![samples[0].0];
+        points.extend(trail.corners());
+        points.push(samples[SEGMENTS - 1].0);
+        pieces += path_pieces(&points, MAX_PIECE_LEN).len();
+        let rendered = Instant::now();
+
+        total.trail += stepped - start;
+        total.collisions += collided - stepped;
+        total.render += rendered - collided;
+        worst = worst.max(rendered - start);
+    }
+
+    let mean = |d: Duration| d / TICKS as u32;
+    let frame = worst * TICKS_PER_FRAME;
+    println!(
+        "{SEGMENTS} segments, {TICKS} ticks, {hits} self hits, {} body pieces a tick",
+        pieces / TICKS
+    );
+    println!(
+        "mean tick: {:?} (trail {:?}, collisions {:?}, body path {:?})",
+        mean(total.total()),
+        mean(total.trail),
+        mean(total.collisions),
+        mean(total.render)
+    );
+    println!("worst tick: {worst:?}");
+    println!(
+        "worst frame: {frame:?} of {FRAME_BUDGET:?}, {}",
+        match frame <= FRAME_BUDGET {
+            true => "within budget",
+            false => "over budget",
+        }
+    );
+}
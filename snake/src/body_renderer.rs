@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::{
+        physics::Position,
+        render_system::{
+            render_data::{RenderAsset, RenderDataBuilderTrait, RenderDataTrait},
+            AssetManager, Elevation, RenderComponent, Renderer,
+        },
+    },
+    utils::{
+        rect::{PointF, Rect},
+        util::AsType,
+    },
+};
+
+use assets::hot_reload::version;
+
+use crate::{
+    _engine::Components,
+    elevations::Elevations,
+    snake::{Direction, SnakePos, SnakeTrail},
+    snake_asset,
+    snake_body::{SnakeBody, SnakeBodyAnim, SNAKE_W},
+    Playing, SnakeAsset,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PieceKind {
+    Straight,
+    LeftTurn,
+    RightTurn,
+    Tail,
+}
+
+impl PieceKind {
+    pub fn asset(&self) -> SnakeAsset {
+        match self {
+            PieceKind::Straight => SnakeAsset::SnakeBody,
+            PieceKind::LeftTurn | PieceKind::RightTurn => SnakeAsset::SnakeBodyCorner,
+            PieceKind::Tail => SnakeAsset::SnakeBodyTail,
+        }
+    }
+}
+
+/// Part of the path the body is drawn along, pooled per kind and hidden when unused
+#[hyperfold_engine::component]
+struct BodyPiece {
+    pub kind: PieceKind,
+    pub idx: usize,
+    /// Texture offset and asset version currently drawn, None while hidden
+    pub drawn: Option<(u32, u32)>,
+}
+
+/// Where to draw a piece this frame
+pub struct Piece {
+    pub kind: PieceKind,
+    /// Before rotation, with the head side at the top
+    pub rect: Rect,
+    pub dir: Direction,
+    /// Distance along the body from the head center to the head side of the piece
+    pub dist: f32,
+}
+
+/// A straight line of the path, from the head side to the tail side
+struct Segment {
+    start: PointF,
+    dir: Direction,
+    len: f32,
+}
+
+impl Segment {
+    /// Point `t` along the segment from its start
+    fn at(&self, t: f32) -> PointF {
+        let v = self.dir.velocity(t);
+        PointF {
+            x: self.start.x - v.x,
+            y: self.start.y - v.y,
+        }
+    }
+}
+
+/// Joins `points` into segments, dropping empty ones and merging straight runs
+fn segments(points: &[PointF]) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    for pair in points.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let Some(dir) = Direction::from_vector(PointF {
+            x: start.x - end.x,
+            y: start.y - end.y,
+        }) else {
+            continue;
+        };
+        let len = (start.x - end.x).abs() + (start.y - end.y).abs();
+        match segments.last_mut() {
+            Some(last) if last.dir == dir => last.len += len,
+            _ => segments.push(Segment { start, dir, len }),
+        }
+    }
+    segments
+}
+
+/// Straight runs are shortened where they meet a corner, runs longer than `max_len`
+/// are split so the texture area never runs off the tiled strip
+pub fn path_pieces(points: &[PointF], max_len: f32) -> Vec<Piece> {
+    let segments = segments(points);
+    let turns = |i: usize, j: usize| match (segments.get(i), segments.get(j)) {
+        (Some(a), Some(b)) => a.dir.is_perpendicular(&b.dir),
+        _ => false,
+    };
+
+    let mut pieces = Vec::new();
+    let mut dist = 0.0;
+    for (i, seg) in segments.iter().enumerate() {
+        let start = match i > 0 && turns(i - 1, i) {
+            true => SNAKE_W / 2.0,
+            false => 0.0,
+        };
+        let end = match turns(i, i + 1) {
+            true => seg.len - SNAKE_W / 2.0,
+            false => seg.len,
+        };
+        let mut t = start;
+        while end - t > 0.0 {
+            let len = (end - t).min(max_len);
+            let center = seg.at(t + len / 2.0);
+            pieces.push(Piece {
+                kind: PieceKind::Straight,
+                rect: Rect::from_center(center.x, center.y, SNAKE_W, len),
+                dir: seg.dir,
+                dist: dist + t,
+            });
+            t += len;
+        }
+
+        // Corner at the tail end of this segment, the snake turns from the next one into this one
+        if let Some(next) = segments.get(i + 1).filter(|_| turns(i, i + 1)) {
+            let pivot = seg.at(seg.len);
+            pieces.push(Piece {
+                kind: match seg.dir == next.dir.left() {
+                    true => PieceKind::LeftTurn,
+                    false => PieceKind::RightTurn,
+                },
+                rect: Rect::from_center(pivot.x, pivot.y, SNAKE_W, SNAKE_W),
+                dir: seg.dir,
+                dist: dist + seg.len - SNAKE_W / 2.0,
+            });
+        }
+        dist += seg.len;
+    }
+
+    if let Some(last) = segments.last() {
+        let center = last.at(last.len + SNAKE_W / 4.0);
+        pieces.push(Piece {
+            kind: PieceKind::Tail,
+            rect: Rect::from_center(center.x, center.y, SNAKE_W, SNAKE_W / 2.0),
+            dir: last.dir,
+            dist,
+        });
+    }
+    pieces
+}
+
+components!(BodyPath, pos: &'a Position, body: &'a SnakeBody);
+
+components!(SnakeBodyAnimRef, anim: &'a SnakeBodyAnim);
+
+components!(
+    BodyPieces,
+    piece: &'a mut BodyPiece,
+    pos: &'a mut Position,
+    tex: &'a mut RenderComponent
+);
+
+/// Draws the body as one path from the head through the trail's corners to the tail
+/// The body entities only move along it
+#[hyperfold_engine::system]
+fn render_snake_body(
+    _: &Update,
+    head: SnakePos,
+    snake: SnakeTrail,
+    bodies: Vec<BodyPath>,
+    SnakeBodyAnimRef { anim, .. }: SnakeBodyAnimRef,
+    pieces_cs: Vec<BodyPieces>,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let body_count = snake.snake.body_count;
+    let tail = bodies
+        .iter()
+        .find(|body| body_count > 1 && body.body.snake_idx == body_count - 1);
+
+    let straight = snake_asset(SnakeAsset::SnakeBody);
+    let rows = straight.tile.map_or(1, |(_, rows)| rows);
+    let max_len = SNAKE_W * rows.saturating_sub(1).max(1) as f32;
+    let pieces = match tail {
+        Some(tail) => {
+            let mut points = vec![head.pos.0.center()];
+            points.extend(snake.snake.trail.corners());
+            points.push(tail.pos.0.center());
+            path_pieces(&points, max_len)
+        }
+        None => Vec::new(),
+    };
+
+    let mut pools: HashMap<PieceKind, Vec<BodyPieces>> = HashMap::new();
+    for piece in pieces_cs {
+        pools.entry(piece.piece.kind).or_default().push(piece);
+    }
+    for pool in pools.values_mut() {
+        pool.sort_unstable_by_key(|piece| piece.piece.idx);
+    }
+
+    // Rows the texture is scrolled by at `dist`, keeping it continuous along the body
+    let h = anim.h.max(1);
+    let offset = |dist: f32| (anim.frame as f32 + dist * h as f32 / SNAKE_W).round() as u32 % h;
+
+    let mut counts = HashMap::new();
+    for piece in pieces {
+        let idx = counts.entry(piece.kind).or_insert(0);
+        let entry = snake_asset(piece.kind.asset());
+        let rotation = piece.dir.rotation(90.0);
+        // Straight pieces scroll their area instead of swapping textures
+        let (key, area) = match piece.kind {
+            PieceKind::Straight => (
+                (0, version(&entry.path)),
+                Some(Rect {
+                    x: 0.0,
+                    y: offset(piece.dist) as f32,
+                    w: entry.w as f32,
+                    h: h as f32 * piece.rect.h / SNAKE_W,
+                }),
+            ),
+            _ => ((offset(piece.dist), version(&entry.path)), None),
+        };
+        let new_asset = |r: &Renderer, am: &mut AssetManager| {
+            entry
+                .asset_at(key.0, piece.kind == PieceKind::RightTurn, r, am)
+                .with_rotation(rotation, None)
+        };
+
+        match pools
+            .get_mut(&piece.kind)
+            .and_then(|pool| pool.get_mut(*idx))
+        {
+            Some(cs) => {
+                if cs.piece.drawn != Some(key) {
+                    *cs.tex = RenderComponent::new(new_asset(r, am));
+                    cs.piece.drawn = Some(key);
+                }
+                cs.tex.try_as_mut(|tex: &mut RenderAsset| {
+                    tex.set_rotation(rotation, None);
+                    tex.set_alpha(255);
+                    if area.is_some() {
+                        tex.set_area(area);
+                    }
+                });
+                cs.pos.0 = piece.rect;
+            }
+            None => {
+                let e = Entity::new();
+                add_components!(
+                    entities,
+                    e,
+                    Playing::Label,
+                    BodyPiece {
+                        kind: piece.kind,
+                        idx: *idx,
+                        drawn: Some(key)
+                    },
+                    Elevation(Elevations::SnakeBody as u8),
+                    RenderComponent::new(new_asset(r, am).with_area(area)),
+                    Position(piece.rect)
+                );
+            }
+        }
+        *idx += 1;
+    }
+
+    // Hide the rest of each pool
+    for (kind, pool) in pools {
+        let used = counts.get(&kind).copied().unwrap_or(0);
+        for BodyPieces { piece, tex, .. } in pool.into_iter().skip(used) {
+            if piece.drawn.take().is_some() {
+                tex.try_as_mut(|tex: &mut RenderAsset| tex.set_alpha(0));
+            }
+        }
+    }
+}
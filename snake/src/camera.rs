@@ -0,0 +1,211 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::render_system::Camera,
+    utils::{
+        rand::{new_rng, Rng},
+        rect::{PointF, Rect},
+    },
+};
+
+use assets::accessibility::accessibility;
+
+use crate::{
+    _engine::Components, display::DisplayCS, fruit::EatFruit, snake::HitWall, stats::Died, Playing,
+};
+
+/// Largest offset in pixels, reached at full trauma
+pub const MAX_SHAKE: f32 = 20.0;
+/// Trauma lost per second
+pub const TRAUMA_DECAY: f32 = 1.5;
+pub const DEATH_TRAUMA: f32 = 0.6;
+pub const WALL_TRAUMA: f32 = 0.5;
+
+/// Zooms in to `scale`, easing in and out over `ramp_ms` on either side of `hold_ms`
+#[derive(Copy, Clone)]
+pub struct Zoom {
+    pub scale: f32,
+    pub ramp_ms: u32,
+    pub hold_ms: u32,
+    pub elapsed: u32,
+}
+
+impl Zoom {
+    pub fn new(scale: f32, ramp_ms: u32, hold_ms: u32) -> Self {
+        Self {
+            scale,
+            ramp_ms,
+            hold_ms,
+            elapsed: 0,
+        }
+    }
+
+    /// A quick zoom in and straight back out
+    pub fn punch(scale: f32, ms: u32) -> Self {
+        Self::new(scale, ms / 2, 0)
+    }
+
+    pub fn done(&self) -> bool {
+        self.elapsed >= self.ramp_ms * 2 + self.hold_ms
+    }
+
+    pub fn current_scale(&self) -> f32 {
+        let ramp = self.ramp_ms.max(1) as f32;
+        let t = match self.elapsed {
+            e if e < self.ramp_ms => e as f32 / ramp,
+            e if e < self.ramp_ms + self.hold_ms => 1.0,
+            e => 1.0 - (e - self.ramp_ms - self.hold_ms) as f32 / ramp,
+        }
+        .clamp(0.0, 1.0);
+        // Smoothstep
+        let t = t * t * (3.0 - 2.0 * t);
+        1.0 + (self.scale - 1.0) * t
+    }
+}
+
+/// Shake and zoom layered on top of a base camera
+/// Gameplay math uses the base camera so it isn't affected by the effects
+#[hyperfold_engine::component(Singleton)]
+struct CameraEffects {
+    /// The camera without effects, its size is taken from the real camera on the first reset
+    pub base: Rect,
+    /// 0 to 1, the shake offset grows with its square
+    pub trauma: f32,
+    pub zoom: Option<Zoom>,
+    /// Whether the camera was moved last frame and needs restoring
+    pub active: bool,
+}
+
+impl CameraEffects {
+    pub fn base_camera(&self) -> Camera {
+        Camera(self.base)
+    }
+
+    /// Ignored with reduced motion on
+    pub fn add_trauma(&mut self, trauma: f32) {
+        if !accessibility().reduced_motion {
+            self.trauma = (self.trauma + trauma).min(1.0);
+        }
+    }
+
+    /// Replaces any zoom in progress, ignored with reduced motion on
+    pub fn zoom(&mut self, zoom: Zoom) {
+        if !accessibility().reduced_motion {
+            self.zoom = Some(zoom);
+        }
+    }
+
+    /// Stops all effects and centers the camera on `center`
+    pub fn reset(&mut self, center: PointF, camera: &mut Camera) {
+        self.trauma = 0.0;
+        self.zoom = None;
+        // The camera may still be zoomed
+        let (w, h) = match self.base.w > 0.0 {
+            true => (self.base.w, self.base.h),
+            false => (camera.0.w, camera.0.h),
+        };
+        self.base = Rect::from_center(center.x, center.y, w, h);
+        self.active = false;
+        camera.0 = self.base;
+    }
+
+    /// Changes the size of the view, e.g. when the window is resized
+    pub fn resize(&mut self, w: f32, h: f32, camera: &mut Camera) {
+        self.base = Rect::from_center(self.base.cx(), self.base.cy(), w, h);
+        camera.0 = self.camera_rect();
+    }
+
+    /// The camera with effects applied
+    pub fn camera_rect(&self) -> Rect {
+        let scale = self.zoom.map_or(1.0, |zoom| zoom.current_scale());
+        let shake = MAX_SHAKE * self.trauma * self.trauma;
+        let (dx, dy) = match shake > 0.0 {
+            true => {
+                let mut rng = new_rng();
+                (
+                    shake * rng.gen_range(-1.0..1.0),
+                    shake * rng.gen_range(-1.0..1.0),
+                )
+            }
+            false => (0.0, 0.0),
+        };
+        Rect::from_center(
+            self.base.cx() + dx,
+            self.base.cy() + dy,
+            self.base.w / scale,
+            self.base.h / scale,
+        )
+    }
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_camera_effects(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        CameraEffects {
+            base: Rect::from_center(0.0, 0.0, 0.0, 0.0),
+            trauma: 0.0,
+            zoom: None,
+            active: false
+        }
+    );
+}
+
+components!(CameraEffectsCS, effects: &'a mut CameraEffects);
+
+#[hyperfold_engine::system]
+fn reset_camera(
+    _: &Playing::OnEnter,
+    CameraEffectsCS { effects, .. }: CameraEffectsCS,
+    DisplayCS { display, .. }: DisplayCS,
+    camera: &mut Camera,
+) {
+    // Sized to the window once it is known, otherwise the engine's camera size is kept
+    display.refresh();
+    if let Some(viewport) = display.viewport {
+        effects.base.w = viewport.w;
+        effects.base.h = viewport.h;
+    }
+    effects.reset(PointF::new(), camera);
+}
+
+/// Only touches the camera while an effect is running, so other games keep control of it
+#[hyperfold_engine::system]
+fn update_camera(
+    update: &Update,
+    CameraEffectsCS { effects, .. }: CameraEffectsCS,
+    camera: &mut Camera,
+) {
+    effects.trauma = (effects.trauma - TRAUMA_DECAY * update.0 as f32 / 1000.0).max(0.0);
+    if let Some(zoom) = &mut effects.zoom {
+        zoom.elapsed += update.0;
+        if zoom.done() {
+            effects.zoom = None;
+        }
+    }
+
+    let active = effects.trauma > 0.0 || effects.zoom.is_some();
+    if active || effects.active {
+        camera.0 = effects.camera_rect();
+    }
+    effects.active = active;
+}
+
+#[hyperfold_engine::system]
+fn eat_zoom(_: &EatFruit, CameraEffectsCS { effects, .. }: CameraEffectsCS) {
+    effects.zoom(Zoom::punch(1.05, 200));
+}
+
+#[hyperfold_engine::system]
+fn wall_shake(_: &HitWall, CameraEffectsCS { effects, .. }: CameraEffectsCS) {
+    effects.add_trauma(WALL_TRAUMA);
+}
+
+#[hyperfold_engine::system]
+fn death_shake(_: &Died, CameraEffectsCS { effects, .. }: CameraEffectsCS) {
+    effects.add_trauma(DEATH_TRAUMA);
+    effects.zoom(Zoom::new(1.15, 150, 600));
+}
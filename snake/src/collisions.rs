@@ -0,0 +1,54 @@
+use hyperfold_engine::{
+    _engine::Entity, add_components, components, ecs::entities::NewEntity, utils::rect::Rect,
+};
+
+use grid::SpatialHash;
+
+use crate::{
+    _engine::Components,
+    snake_body::{SNAKE_HB_W, SNAKE_W},
+    Playing, HALF_W,
+};
+
+/// What a box in the hash belongs to
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Collider {
+    /// A segment of any snake, heads included
+    Segment(Entity),
+    Fruit(Entity),
+    /// One of the board's four edges
+    Wall(u8),
+}
+
+/// Every box snakes can hit, updated as things move so checks only look nearby
+#[hyperfold_engine::component(Singleton)]
+struct Collisions(pub SpatialHash<Collider>);
+
+#[hyperfold_engine::system(Init)]
+fn new_collisions(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(entities, e, Collisions(SpatialHash::new(SNAKE_W)));
+}
+
+components!(CollisionsCS, collisions: &'a mut Collisions);
+
+/// Walls reach in so a head's hit box touches one as its sprite reaches the edge
+fn walls() -> [Rect; 4] {
+    let inner = HALF_W - (SNAKE_W - SNAKE_HB_W) / 2.0;
+    let (mid, len) = (inner + SNAKE_W / 2.0, 2.0 * (HALF_W + SNAKE_W));
+    [
+        Rect::from_center(0.0, -mid, len, SNAKE_W),
+        Rect::from_center(0.0, mid, len, SNAKE_W),
+        Rect::from_center(-mid, 0.0, SNAKE_W, len),
+        Rect::from_center(mid, 0.0, SNAKE_W, len),
+    ]
+}
+
+/// Segments and fruit are added as they spawn
+#[hyperfold_engine::system]
+fn reset_collisions(_: &Playing::OnEnter, CollisionsCS { collisions, .. }: CollisionsCS) {
+    collisions.0.clear();
+    for (i, wall) in walls().into_iter().enumerate() {
+        collisions.0.insert(Collider::Wall(i as u8), wall);
+    }
+}
@@ -0,0 +1,488 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::{
+        event_system::events::Key,
+        physics::Position,
+        render_system::{
+            drawable::Canvas,
+            font::FontData,
+            render_data::{Fit, RenderDataBuilderTrait, RenderTexture},
+            render_text::RenderText,
+            AssetManager, Elevation, RenderComponent, Renderer, Texture,
+        },
+    },
+    sdl2::{SDL_Color, SDL_KeyCode},
+    utils::{
+        colors::WHITE,
+        rect::{Align, Rect},
+    },
+};
+
+use assets::{
+    accessibility::{accessibility, set_accessibility},
+    strings::{language, languages, set_language, tr, tr_with},
+};
+use input::{Action, Binding, Bindings, GamepadButton};
+
+use crate::{
+    _engine::{Components, Events},
+    display::ToggleScaling,
+    elevations::Elevations,
+    ghost::ToggleGhost,
+    scaled_text,
+    theme::{set_theme, theme, themes},
+    GameOver, GameOverEids, Playing, W_F, W_I,
+};
+
+pub const LINE_H: u32 = 24;
+pub const MARGIN: f32 = 20.0;
+pub const SELECTED_COLOR: SDL_Color = SDL_Color {
+    r: 255,
+    g: 220,
+    b: 80,
+    a: 255,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum SnakeAction {
+    TurnUp,
+    TurnDown,
+    TurnLeft,
+    TurnRight,
+    /// Relative to the head's heading
+    RotateLeft,
+    RotateRight,
+    Restart,
+    Pause,
+    Menu,
+    Mute,
+    VolumeDown,
+    VolumeUp,
+    Fullscreen,
+    Stats,
+}
+
+impl Action for SnakeAction {
+    const GAME: &'static str = "snake";
+    const ALL: &'static [Self] = &[
+        SnakeAction::TurnUp,
+        SnakeAction::TurnDown,
+        SnakeAction::TurnLeft,
+        SnakeAction::TurnRight,
+        SnakeAction::RotateLeft,
+        SnakeAction::RotateRight,
+        SnakeAction::Restart,
+        SnakeAction::Pause,
+        SnakeAction::Menu,
+        SnakeAction::Mute,
+        SnakeAction::VolumeDown,
+        SnakeAction::VolumeUp,
+        SnakeAction::Fullscreen,
+        SnakeAction::Stats,
+    ];
+
+    fn id(&self) -> &'static str {
+        match self {
+            SnakeAction::TurnUp => "turn_up",
+            SnakeAction::TurnDown => "turn_down",
+            SnakeAction::TurnLeft => "turn_left",
+            SnakeAction::TurnRight => "turn_right",
+            SnakeAction::RotateLeft => "rotate_left",
+            SnakeAction::RotateRight => "rotate_right",
+            SnakeAction::Restart => "restart",
+            SnakeAction::Pause => "pause",
+            SnakeAction::Menu => "menu",
+            SnakeAction::Mute => "mute",
+            SnakeAction::VolumeDown => "volume_down",
+            SnakeAction::VolumeUp => "volume_up",
+            SnakeAction::Fullscreen => "fullscreen",
+            SnakeAction::Stats => "stats",
+        }
+    }
+
+    fn defaults(&self) -> Vec<Binding> {
+        let (key, button) = match self {
+            SnakeAction::TurnUp => (SDL_KeyCode::SDLK_w, Some(GamepadButton::DPadUp)),
+            SnakeAction::TurnDown => (SDL_KeyCode::SDLK_s, Some(GamepadButton::DPadDown)),
+            SnakeAction::TurnLeft => (SDL_KeyCode::SDLK_a, Some(GamepadButton::DPadLeft)),
+            SnakeAction::TurnRight => (SDL_KeyCode::SDLK_d, Some(GamepadButton::DPadRight)),
+            SnakeAction::RotateLeft => (SDL_KeyCode::SDLK_q, Some(GamepadButton::LeftShoulder)),
+            SnakeAction::RotateRight => (SDL_KeyCode::SDLK_e, Some(GamepadButton::RightShoulder)),
+            SnakeAction::Restart => (SDL_KeyCode::SDLK_r, Some(GamepadButton::South)),
+            SnakeAction::Pause => (SDL_KeyCode::SDLK_p, Some(GamepadButton::Start)),
+            SnakeAction::Menu => (SDL_KeyCode::SDLK_TAB, Some(GamepadButton::Back)),
+            SnakeAction::Mute => (SDL_KeyCode::SDLK_m, None),
+            SnakeAction::VolumeDown => (SDL_KeyCode::SDLK_MINUS, None),
+            SnakeAction::VolumeUp => (SDL_KeyCode::SDLK_EQUALS, None),
+            SnakeAction::Fullscreen => (SDL_KeyCode::SDLK_F11, None),
+            SnakeAction::Stats => (SDL_KeyCode::SDLK_y, Some(GamepadButton::North)),
+        };
+        let mut bindings = vec![Binding::Key(key as i32)];
+        bindings.extend(button.map(Binding::Button));
+        bindings
+    }
+}
+
+impl SnakeAction {
+    /// Translated name shown to players
+    pub fn label(&self) -> String {
+        tr(&format!("action.{}", self.id()))
+    }
+}
+
+/// Fired for every input that is bound to an action
+#[hyperfold_engine::event]
+struct SnakeInput(pub SnakeAction);
+
+#[hyperfold_engine::state]
+struct ControlsMenu;
+
+#[hyperfold_engine::component(Singleton)]
+struct Controls {
+    pub bindings: Bindings<SnakeAction>,
+    /// Index into `SnakeAction::ALL` on the controls screen
+    pub selected: usize,
+    /// Waiting for an input to bind to the selected action
+    pub awaiting: bool,
+    pub status: String,
+    /// The controls screen needs redrawing
+    pub dirty: bool,
+    /// Set once snake starts, other games launch from the same binary and keep their keys
+    pub active: bool,
+}
+
+impl Controls {
+    /// Starts a binding, or fires the action it triggers
+    pub fn input(&mut self, binding: Binding, events: &mut dyn Events) {
+        if !self.active {
+            return;
+        }
+        if !self.awaiting {
+            if let Some(action) = self.bindings.action(binding) {
+                events.new_event(SnakeInput(action));
+            }
+            return;
+        }
+
+        self.awaiting = false;
+        self.dirty = true;
+        let action = SnakeAction::ALL[self.selected];
+        let name = binding.name();
+        self.status = match self.bindings.replace(action, binding) {
+            Ok(()) => {
+                self.bindings.save();
+                tr_with(
+                    "controls_bound",
+                    &[("binding", &name), ("action", &action.label())],
+                )
+            }
+            Err(other) => tr_with(
+                "controls_conflict",
+                &[("binding", &name), ("action", &other.label())],
+            ),
+        };
+    }
+
+    /// Keys on the controls screen, these are fixed so it can't be locked out by bad bindings
+    fn navigate(&mut self, key: SDL_KeyCode, events: &mut dyn Events) {
+        let n = SnakeAction::ALL.len();
+        match key {
+            SDL_KeyCode::SDLK_UP => self.selected = (self.selected + n - 1) % n,
+            SDL_KeyCode::SDLK_DOWN => self.selected = (self.selected + 1) % n,
+            SDL_KeyCode::SDLK_RETURN => {
+                self.awaiting = true;
+                self.status = String::new();
+            }
+            SDL_KeyCode::SDLK_BACKSPACE => {
+                let action = SnakeAction::ALL[self.selected];
+                self.status = match self.bindings.reset(action) {
+                    Ok(()) => tr_with("controls_reset", &[("action", &action.label())]),
+                    Err(other) => tr_with("controls_reset_conflict", &[("action", &other.label())]),
+                };
+                self.bindings.save();
+            }
+            SDL_KeyCode::SDLK_l => {
+                let languages = languages();
+                let current = languages.iter().position(|l| *l == language());
+                if let Some(next) = current.map_or(languages.first(), |i| {
+                    languages.get((i + 1) % languages.len())
+                }) {
+                    set_language(next);
+                }
+                self.status = String::new();
+            }
+            SDL_KeyCode::SDLK_t => {
+                let themes = themes();
+                let current = themes.iter().position(|t| *t == theme().id);
+                if let Some(next) =
+                    current.map_or(themes.first(), |i| themes.get((i + 1) % themes.len()))
+                {
+                    self.status = match set_theme(next) {
+                        Ok(()) => tr_with("controls_theme", &[("theme", &theme().name)]),
+                        Err(e) => {
+                            eprintln!("{e}");
+                            tr_with("controls_theme_invalid", &[("theme", next)])
+                        }
+                    };
+                }
+            }
+            SDL_KeyCode::SDLK_h
+            | SDL_KeyCode::SDLK_c
+            | SDL_KeyCode::SDLK_n
+            | SDL_KeyCode::SDLK_z => self.status = toggle_accessibility(key),
+            // The display and ghost set the status
+            SDL_KeyCode::SDLK_i => events.new_event(ToggleScaling),
+            SDL_KeyCode::SDLK_g => events.new_event(ToggleGhost),
+            SDL_KeyCode::SDLK_ESCAPE => events.set_state(GameOver::Data),
+            _ => return,
+        }
+        self.dirty = true;
+    }
+}
+
+/// Changes the setting for an accessibility key, returning the new value to show
+/// Board and sprite changes are applied when the next game starts
+fn toggle_accessibility(key: SDL_KeyCode) -> String {
+    let on_off = |on: bool| tr(if on { "access_on" } else { "access_off" });
+    let mut settings = accessibility();
+    let status = match key {
+        SDL_KeyCode::SDLK_h => {
+            settings.high_contrast = !settings.high_contrast;
+            tr_with(
+                "access_contrast",
+                &[("value", &on_off(settings.high_contrast))],
+            )
+        }
+        SDL_KeyCode::SDLK_c => {
+            settings.palette = settings.next_palette();
+            let id = settings.palette.map_or("none", |p| p.id());
+            tr_with(
+                "access_palette",
+                &[("value", &tr(&format!("palette.{id}")))],
+            )
+        }
+        SDL_KeyCode::SDLK_n => {
+            settings.reduced_motion = !settings.reduced_motion;
+            tr_with(
+                "access_motion",
+                &[("value", &on_off(settings.reduced_motion))],
+            )
+        }
+        _ => {
+            settings.text_scale = settings.next_text_scale();
+            tr_with(
+                "access_text",
+                &[("value", &format!("{}%", settings.text_scale * 100.0))],
+            )
+        }
+    };
+    set_accessibility(settings);
+    status
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_controls(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Controls {
+            bindings: Bindings::load(),
+            selected: 0,
+            awaiting: false,
+            status: String::new(),
+            dirty: false,
+            active: false
+        }
+    );
+}
+
+components!(ControlsCS, controls: &'a mut Controls);
+
+/// Snake's states only follow its own, so it stays active from then on
+#[hyperfold_engine::system]
+fn activate_controls(_: &Playing::OnEnter, ControlsCS { controls, .. }: ControlsCS) {
+    controls.active = true;
+}
+
+#[hyperfold_engine::component(Singleton)]
+struct ControlsScreen;
+
+components!(labels(ControlsScreen), ControlsScreenEids);
+
+components!(
+    labels(ControlsScreen),
+    ControlsScreenTex,
+    tex: &'a mut RenderComponent
+);
+
+#[hyperfold_engine::system]
+fn key_input(
+    key: &Key,
+    ControlsCS { controls, .. }: ControlsCS,
+    screens: Vec<ControlsScreenEids>,
+    events: &mut dyn Events,
+) {
+    if !key.0.pressed() {
+        return;
+    }
+    match !screens.is_empty() && !controls.awaiting {
+        true => controls.navigate(key.0.key, events),
+        false => controls.input(Binding::Key(key.0.key as i32), events),
+    }
+}
+
+/// Draws text a line at a time onto a menu screen
+pub struct Lines<'a> {
+    pub tex: &'a Texture,
+    pub font: String,
+    pub line_h: u32,
+}
+
+impl Lines<'_> {
+    /// Lines that fit on the screen
+    pub fn rows(&self) -> usize {
+        ((W_F - MARGIN * 2.0) / self.line_h as f32) as usize
+    }
+
+    pub fn draw(
+        &self,
+        text: &str,
+        line: usize,
+        color: SDL_Color,
+        r: &Renderer,
+        am: &mut AssetManager,
+    ) {
+        let rect = Rect {
+            x: MARGIN,
+            y: MARGIN + (line as u32 * self.line_h) as f32,
+            w: W_F - MARGIN * 2.0,
+            h: self.line_h as f32,
+        };
+        // Long lines shrink to fit the width
+        let font = FontData {
+            w: Some(rect.w as u32),
+            h: Some(self.line_h * 3 / 4),
+            sample: text.to_string(),
+            file: self.font.clone(),
+        };
+        let mut rt = RenderText::new(font)
+            .with_text(text)
+            .with_text_color(color)
+            .with_dest_align(Align::TopLeft, Align::Center)
+            .with_dest_fit(Fit::None)
+            .with_dest_rect(rect);
+        rt.render_text(rect, r, am);
+        self.tex.draw(r, &mut rt);
+    }
+}
+
+/// Actions scroll when the text is too large for all of them to fit
+pub fn controls_texture(controls: &Controls, r: &Renderer, am: &mut AssetManager) -> Texture {
+    let theme = theme();
+    let tex = Texture::new(r, W_I, W_I, theme.menu_overlay());
+    let lines = Lines {
+        tex: &tex,
+        font: theme.font(),
+        line_h: scaled_text(LINE_H),
+    };
+    let help = [
+        tr("controls_help_select"),
+        tr("controls_help_back"),
+        tr("controls_help_access"),
+    ];
+    // Title and status with a gap after each, then the help
+    let rows = lines.rows();
+    let list_rows = rows.saturating_sub(help.len() + 4).max(1);
+    let first = (controls.selected + 1).saturating_sub(list_rows);
+
+    lines.draw(&tr("controls_title"), 0, WHITE, r, am);
+    for (i, action) in SnakeAction::ALL
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(list_rows)
+    {
+        let bindings = match (
+            controls.awaiting && i == controls.selected,
+            controls.bindings.get(*action),
+        ) {
+            (true, _) => tr("controls_awaiting"),
+            (false, []) => tr("controls_unbound"),
+            (false, bindings) => bindings
+                .iter()
+                .map(|b| b.name())
+                .collect::<Vec<_>>()
+                .join(" / "),
+        };
+        let color = match i == controls.selected {
+            true => SELECTED_COLOR,
+            false => WHITE,
+        };
+        lines.draw(
+            &format!("{}: {bindings}", action.label()),
+            i - first + 2,
+            color,
+            r,
+            am,
+        );
+    }
+    let status_line = list_rows + 3;
+    lines.draw(&controls.status, status_line, WHITE, r, am);
+    for (i, text) in help.iter().enumerate() {
+        lines.draw(text, status_line + 1 + i, WHITE, r, am);
+    }
+    tex
+}
+
+#[hyperfold_engine::system]
+fn open_controls(input: &SnakeInput, game_over: Vec<GameOverEids>, events: &mut dyn Events) {
+    if input.0 == SnakeAction::Menu && !game_over.is_empty() {
+        events.set_state(ControlsMenu::Data);
+    }
+}
+
+#[hyperfold_engine::system]
+fn controls_screen(
+    _: &ControlsMenu::OnEnter,
+    ControlsCS { controls, .. }: ControlsCS,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    controls.selected = 0;
+    controls.awaiting = false;
+    controls.status = String::new();
+    controls.dirty = false;
+
+    let tex = controls_texture(controls, r, am);
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        ControlsScreen,
+        ControlsMenu::Label,
+        Elevation(Elevations::ControlsScreen as u8),
+        RenderComponent::new(RenderTexture::new(Some(tex))),
+        Position(Rect::from_center(0.0, 0.0, W_F, W_F))
+    );
+}
+
+#[hyperfold_engine::system]
+fn redraw_controls(
+    _: &Update,
+    ControlsCS { controls, .. }: ControlsCS,
+    screens: Vec<ControlsScreenTex>,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    if !controls.dirty {
+        return;
+    }
+    controls.dirty = false;
+    for ControlsScreenTex { tex, .. } in screens {
+        *tex = RenderComponent::new(RenderTexture::new(Some(controls_texture(controls, r, am))));
+    }
+}
@@ -0,0 +1,158 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::render_system::Camera,
+};
+
+use assets::{
+    settings::{read_key_values, save_file},
+    strings::{tr, tr_with},
+};
+use grid::{Scaling, Viewport};
+use input::Window;
+
+use crate::{
+    _engine::Components,
+    camera::CameraEffectsCS,
+    controls::{ControlsCS, SnakeAction, SnakeInput},
+    GRID,
+};
+
+/// Shared by every game
+pub const DISPLAY_FILE: &str = "save/display.txt";
+
+/// Switches between integer and fractional scaling
+#[hyperfold_engine::event]
+struct ToggleScaling;
+
+#[hyperfold_engine::component(Singleton)]
+struct Display {
+    pub window: Window,
+    pub scaling: Scaling,
+    /// Applied once the window exists
+    pub fullscreen: bool,
+    /// `None` until the window size is known
+    pub viewport: Option<Viewport>,
+}
+
+impl Display {
+    /// Reads `<setting> <value>` lines, a missing file means windowed with integer scaling
+    fn load() -> Self {
+        let mut display = Self {
+            window: Window::new(),
+            scaling: Scaling::Integer,
+            fullscreen: false,
+            viewport: None,
+        };
+        for (key, value) in read_key_values(DISPLAY_FILE) {
+            match key.as_str() {
+                "fullscreen" => display.fullscreen = value == "on",
+                "scaling" => display.scaling = Scaling::from_id(&value).unwrap_or(display.scaling),
+                _ => (),
+            }
+        }
+        display
+    }
+
+    fn save(&self) {
+        let text = format!(
+            "fullscreen {}\nscaling {}\n",
+            if self.fullscreen { "on" } else { "off" },
+            self.scaling.id()
+        );
+        save_file(DISPLAY_FILE, "display settings", text);
+    }
+
+    fn fit(&mut self, (w, h): (u32, u32)) {
+        self.viewport = Some(Viewport::fit(
+            GRID.width(),
+            GRID.height(),
+            w,
+            h,
+            self.scaling,
+        ));
+    }
+
+    /// Polls the window, returns whether the viewport changed
+    pub fn refresh(&mut self) -> bool {
+        let first = self.window.size().is_none();
+        let Some(size) = self.window.poll() else {
+            return false;
+        };
+        if first && self.fullscreen {
+            // The fullscreen size is picked up on the next poll
+            self.window.set_fullscreen(true);
+        }
+        self.fit(size);
+        true
+    }
+
+    pub fn toggle_fullscreen(&mut self) {
+        self.fullscreen = self.window.set_fullscreen(!self.fullscreen);
+        self.save();
+    }
+
+    pub fn toggle_scaling(&mut self) {
+        self.scaling = match self.scaling {
+            Scaling::Integer => Scaling::Fractional,
+            Scaling::Fractional => Scaling::Integer,
+        };
+        if let Some(size) = self.window.size() {
+            self.fit(size);
+        }
+        self.save();
+    }
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_display(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(entities, e, Display::load());
+}
+
+components!(DisplayCS, display: &'a mut Display);
+
+/// Resizes the camera with the window, world units are the same at every scale
+/// The camera is left alone until a game has started, so other games keep control of it
+#[hyperfold_engine::system]
+fn update_display(
+    _: &Update,
+    DisplayCS { display, .. }: DisplayCS,
+    CameraEffectsCS { effects, .. }: CameraEffectsCS,
+    camera: &mut Camera,
+) {
+    if !display.refresh() || effects.base.w <= 0.0 {
+        return;
+    }
+    if let Some(viewport) = display.viewport {
+        effects.resize(viewport.w, viewport.h, camera);
+    }
+}
+
+#[hyperfold_engine::system]
+fn fullscreen_input(input: &SnakeInput, DisplayCS { display, .. }: DisplayCS) {
+    if input.0 == SnakeAction::Fullscreen {
+        display.toggle_fullscreen();
+    }
+}
+
+#[hyperfold_engine::system]
+fn toggle_scaling(
+    _: &ToggleScaling,
+    DisplayCS { display, .. }: DisplayCS,
+    ControlsCS { controls, .. }: ControlsCS,
+    CameraEffectsCS { effects, .. }: CameraEffectsCS,
+    camera: &mut Camera,
+) {
+    display.toggle_scaling();
+    if let (Some(viewport), true) = (display.viewport, effects.base.w > 0.0) {
+        effects.resize(viewport.w, viewport.h, camera);
+    }
+    let id = display.scaling.id();
+    controls.status = tr_with(
+        "display_scaling",
+        &[("value", &tr(&format!("scaling.{id}")))],
+    );
+    controls.dirty = true;
+}
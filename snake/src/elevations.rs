@@ -1,9 +1,15 @@
 #[repr(u8)]
 pub enum Elevations {
     Background = 0,
+    FruitCue,
     Fruit,
     FruitEffect,
+    Ghost,
+    SnakeBody,
     Snake,
+    Particles,
     GameOverScreen,
     GameOverText,
+    ControlsScreen,
+    StatsScreen,
 }
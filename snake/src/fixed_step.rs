@@ -0,0 +1,171 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::physics::Position,
+    utils::rect::{Align, PointF},
+};
+
+use input::Args;
+
+use crate::{
+    _engine::{Components, Events},
+    Playing,
+};
+
+/// Snake logic steps per second, independent of the frame rate
+/// Set with `--tick_hz <hz>`
+pub const DEFAULT_TICK_HZ: u32 = 120;
+/// Faster rates would mostly spend frames catching up
+pub const MAX_TICK_HZ: u32 = 1000;
+/// Time beyond this in one frame is dropped so a long hitch doesn't stall the game catching up
+pub const MAX_FRAME_MS: u32 = 250;
+
+/// One fixed step of snake logic
+#[hyperfold_engine::event]
+struct Tick {
+    /// Steps since the game started
+    pub tick: u64,
+    /// Length of a step in seconds
+    pub dt: f32,
+}
+
+/// Fired after each frame's ticks, `alpha` is how far the frame is between the last two ticks
+#[hyperfold_engine::event]
+struct Interpolate(pub f32);
+
+#[hyperfold_engine::component(Singleton)]
+struct FixedStep {
+    pub hz: u32,
+    /// Unsimulated time in microseconds
+    pub accumulator: u64,
+    pub tick: u64,
+}
+
+impl FixedStep {
+    pub fn new(hz: u32) -> Self {
+        Self {
+            hz: hz.max(1),
+            accumulator: 0,
+            tick: 0,
+        }
+    }
+
+    pub fn step_us(&self) -> u64 {
+        1_000_000 / self.hz as u64
+    }
+
+    pub fn dt(&self) -> f32 {
+        1.0 / self.hz as f32
+    }
+
+    /// Restarts the tick count, e.g. for a new game
+    pub fn reset(&mut self) {
+        self.accumulator = 0;
+        self.tick = 0;
+    }
+}
+
+/// Falls back to `DEFAULT_TICK_HZ` without a rate or with one outside `1..=MAX_TICK_HZ`
+pub fn tick_hz(hz: Option<u32>) -> u32 {
+    match hz {
+        Some(hz @ 1..=MAX_TICK_HZ) => hz,
+        Some(hz) => {
+            eprintln!(
+                "Ignoring --tick_hz {hz}: expected 1 to {MAX_TICK_HZ}, using {DEFAULT_TICK_HZ}"
+            );
+            DEFAULT_TICK_HZ
+        }
+        None => DEFAULT_TICK_HZ,
+    }
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_fixed_step(entities: &mut dyn Components) {
+    let e = Entity::new();
+    let hz = tick_hz(Args::get().value("tick_hz"));
+    add_components!(entities, e, FixedStep::new(hz));
+}
+
+components!(FixedStepCS, step: &'a mut FixedStep);
+
+#[hyperfold_engine::system]
+fn reset_fixed_step(_: &Playing::OnEnter, FixedStepCS { step, .. }: FixedStepCS) {
+    step.reset();
+}
+
+#[hyperfold_engine::system]
+fn fixed_update(update: &Update, FixedStepCS { step, .. }: FixedStepCS, events: &mut dyn Events) {
+    step.accumulator += update.0.min(MAX_FRAME_MS) as u64 * 1000;
+    let step_us = step.step_us();
+    while step.accumulator >= step_us {
+        step.accumulator -= step_us;
+        events.new_event(Tick {
+            tick: step.tick,
+            dt: step.dt(),
+        });
+        step.tick += 1;
+    }
+    events.new_event(Interpolate(step.accumulator as f32 / step_us as f32));
+}
+
+/// Simulated center of an entity moved by ticks, drawn between `prev` and `pos`
+#[hyperfold_engine::component]
+struct Motion {
+    pub prev: PointF,
+    pub pos: PointF,
+}
+
+impl Motion {
+    pub fn new(pos: PointF) -> Self {
+        Self { prev: pos, pos }
+    }
+
+    /// Call at the start of each tick, before moving
+    pub fn begin_tick(&mut self) {
+        self.prev = self.pos;
+    }
+
+    pub fn lerp(&self, alpha: f32) -> PointF {
+        PointF {
+            x: self.prev.x + (self.pos.x - self.prev.x) * alpha,
+            y: self.prev.y + (self.pos.y - self.prev.y) * alpha,
+        }
+    }
+}
+
+components!(Interpolated, motion: &'a Motion, pos: &'a mut Position);
+
+#[hyperfold_engine::system]
+fn interpolate(alpha: &Interpolate, entities: Vec<Interpolated>) {
+    for Interpolated { motion, pos, .. } in entities {
+        let center = motion.lerp(alpha.0);
+        pos.0
+            .set_pos(center.x, center.y, Align::Center, Align::Center);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tick_hz_defaults_and_validates() {
+        assert_eq!(tick_hz(None), DEFAULT_TICK_HZ);
+        assert_eq!(tick_hz(Some(60)), 60);
+        assert_eq!(tick_hz(Some(1)), 1);
+        assert_eq!(tick_hz(Some(MAX_TICK_HZ)), MAX_TICK_HZ);
+        assert_eq!(tick_hz(Some(0)), DEFAULT_TICK_HZ);
+        assert_eq!(tick_hz(Some(MAX_TICK_HZ + 1)), DEFAULT_TICK_HZ);
+    }
+
+    #[test]
+    fn steps_add_up_to_a_second() {
+        for hz in [1, 60, DEFAULT_TICK_HZ, MAX_TICK_HZ] {
+            let step = FixedStep::new(hz);
+            assert!((step.dt() * hz as f32 - 1.0).abs() < 1e-4);
+            assert!(step.step_us() * hz as u64 <= 1_000_000);
+        }
+        assert_eq!(FixedStep::new(0).hz, 1);
+    }
+}
@@ -1,34 +1,119 @@
 use hyperfold_engine::{
     _engine::Entity,
     add_components, components,
-    ecs::{
-        entities::{EntityTrash, NewEntity},
-        events::core::Update,
-    },
+    ecs::entities::{EntityTrash, NewEntity},
     framework::{
         physics::Position,
         render_system::{
-            render_data::RenderAsset, AssetManager, Camera, Elevation, RenderComponent, Renderer,
+            drawable::Canvas,
+            render_data::RenderTexture,
+            shapes::{Rectangle, ShapeTrait},
+            AssetManager, Elevation, RenderComponent, Renderer, Texture,
         },
     },
+    sdl2::SDL_Color,
     utils::{
+        colors::{BLACK, WHITE},
         rand::{new_rng, Rng},
-        rect::{Align, Point, Rect},
+        rect::{Align, Point, PointF, Rect},
     },
 };
 
+use assets::accessibility::accessibility;
+
 use crate::{
     _engine::{Components, Events},
+    camera::CameraEffectsCS,
+    collisions::{Collider, Collisions, CollisionsCS},
     elevations::Elevations,
-    fruit_effect::new_fruit_effect,
+    fixed_step::Tick,
+    fruit_effect::{n_fruit_effects, new_fruit_effect, PowerUp},
+    hot_reload::SpriteAsset,
     pos_to_square,
     snake::SnakePos,
-    square_to_pos, Playing, N_SQUARES,
+    square_to_pos, Playing, SnakeAsset, GRID,
 };
 
+pub const FRUIT_W: f32 = 25.0;
+pub const CUE_W: f32 = 35.0;
+
 #[hyperfold_engine::component]
 struct Fruit;
 
+/// Places fruit from the run's seed, so a run can be played again on the same board
+#[hyperfold_engine::component(Singleton)]
+struct FruitRng {
+    pub seed: u64,
+    pub state: u64,
+}
+
+impl FruitRng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// SplitMix64, the same sequence on every platform
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// In `0..n`
+    pub fn below(&mut self, n: u32) -> u32 {
+        (self.next_u64() % n.max(1) as u64) as u32
+    }
+}
+
+/// A new seed for runs that aren't replaying another
+pub fn random_seed() -> u64 {
+    new_rng().gen_range(0..u64::MAX)
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_fruit_rng(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(entities, e, FruitRng::new(random_seed()));
+}
+
+components!(FruitRngCS, rng: &'a mut FruitRng);
+
+/// Outline drawn around fruit with a colorblind palette, so fruit isn't told apart by color alone
+#[hyperfold_engine::component]
+struct FruitCue(pub Entity);
+
+/// A white square with a dark edge, visible on light and dark boards
+fn cue_texture(r: &Renderer) -> Texture {
+    let tex = Texture::new(
+        r,
+        CUE_W as u32,
+        CUE_W as u32,
+        SDL_Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        },
+    );
+    let rect = Rect {
+        x: 0.0,
+        y: 0.0,
+        w: CUE_W,
+        h: CUE_W,
+    };
+    tex.draw(
+        r,
+        &mut Rectangle::new().set_color(WHITE).border(rect, -4.0, false),
+    );
+    tex.draw(
+        r,
+        &mut Rectangle::new().set_color(BLACK).border(rect, -1.0, false),
+    );
+    tex
+}
+
 #[hyperfold_engine::event]
 struct EatFruit(pub Entity);
 
@@ -42,60 +127,105 @@ pub fn new_fruit(
     entities: &mut dyn Components,
     r: &Renderer,
     am: &mut AssetManager,
-    camera: &Camera,
+    CameraEffectsCS {
+        effects: camera, ..
+    }: CameraEffectsCS,
+    CollisionsCS { collisions, .. }: CollisionsCS,
+    FruitRngCS { rng, .. }: FruitRngCS,
+    events: &mut dyn Events,
 ) {
     let snake_pos = pos_to_square(snake.pos.0.center(), camera);
-    let mut rng = new_rng();
     let mut pos;
     while {
         pos = Point {
-            x: rng.gen_range(0..N_SQUARES) as i32,
-            y: rng.gen_range(0..N_SQUARES) as i32,
+            x: rng.below(GRID.w) as i32,
+            y: rng.below(GRID.h) as i32,
         };
 
         pos == snake_pos
     } {}
     let pos = square_to_pos(pos, camera);
+    let fruit = add_fruit(pos, collisions, entities, r, am);
+
+    // Fruit effect, it flashes so it is left out with reduced motion
+    if !accessibility().reduced_motion {
+        let img = new_rng().gen_range(0..n_fruit_effects());
+        new_fruit_effect(img, fruit, pos, 0, entities, r, am);
+        events.new_event(PowerUp(fruit));
+    }
+}
 
-    // Fruit
+/// Adds a fruit centered at `pos`, with a cue around it if a colorblind palette is on
+pub fn add_fruit(
+    pos: PointF,
+    collisions: &mut Collisions,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) -> Entity {
     let fruit = Entity::new();
+    let rect = Rect::from(pos.x, pos.y, FRUIT_W, FRUIT_W, Align::Center, Align::Center);
+    collisions.0.insert(Collider::Fruit(fruit), rect);
+    let sprite = SpriteAsset::frame(SnakeAsset::Fruit, 0);
     add_components!(
         entities,
         fruit,
         Fruit,
         Playing::Label,
         Elevation(Elevations::Fruit as u8),
-        RenderComponent::new(RenderAsset::from_file("res/snake/fruit.png", r, am)),
-        Position(Rect::from(
-            pos.x,
-            pos.y,
-            25.0,
-            25.0,
-            Align::Center,
-            Align::Center
-        ))
+        RenderComponent::new(sprite.render_asset(r, am)),
+        sprite,
+        Position(rect)
     );
 
-    // Fruit effect
-    let img = new_rng().gen_range(0..3);
-    new_fruit_effect(img, fruit, pos, entities, r, am);
+    if accessibility().palette.is_some() {
+        let e = Entity::new();
+        add_components!(
+            entities,
+            e,
+            FruitCue(fruit),
+            Playing::Label,
+            Elevation(Elevations::FruitCue as u8),
+            RenderComponent::new(RenderTexture::new(Some(cue_texture(r)))),
+            Position(Rect::from_center(pos.x, pos.y, CUE_W, CUE_W))
+        );
+    }
+    fruit
 }
 
 components!(labels(Fruit), FruitPos, pos: &'a Position);
 
 #[hyperfold_engine::system]
 fn collide_fruit(
-    _: &Update,
-    fruits: Vec<FruitPos>,
+    _: &Tick,
     snake: SnakePos,
+    CollisionsCS { collisions, .. }: CollisionsCS,
     trash: &mut EntityTrash,
     events: &mut dyn Events,
 ) {
-    for fruit in fruits {
-        if fruit.pos.0.intersects(&snake.hit_box.0) {
-            trash.0.push(*fruit.eid);
-            events.new_event(EatFruit(*fruit.eid));
-            events.new_event(SpawnFruit);
-        }
+    let eaten: Vec<_> = collisions
+        .0
+        .query(&snake.hit_box.0)
+        .filter_map(|hit| match hit {
+            Collider::Fruit(e) => Some(e),
+            _ => None,
+        })
+        .collect();
+    for fruit in eaten {
+        collisions.0.remove(Collider::Fruit(fruit));
+        trash.0.push(fruit);
+        events.new_event(EatFruit(fruit));
+        events.new_event(SpawnFruit);
     }
 }
+
+components!(FruitCues, cue: &'a FruitCue);
+
+#[hyperfold_engine::system]
+fn remove_fruit_cue(fruit: &EatFruit, cues: Vec<FruitCues>, trash: &mut EntityTrash) {
+    trash.0.extend(
+        cues.into_iter()
+            .filter_map(|cue| (cue.cue.0 == fruit.0).then_some(cue.eid))
+            .collect::<Vec<_>>(),
+    );
+}
@@ -1,4 +1,3 @@
-use crate::{_engine::Components, elevations::Elevations, fruit::EatFruit, Playing};
 use hyperfold_engine::{
     _engine::Entity,
     add_components, components,
@@ -20,33 +19,48 @@ use hyperfold_engine::{
     },
 };
 
-pub fn fruit_effect_image(i: u8) -> String {
-    format!("res/snake/fruit_effects_{i}.png")
+use crate::{
+    _engine::Components, elevations::Elevations, fruit::EatFruit, hot_reload::SpriteAsset,
+    snake_asset, Playing, SnakeAsset,
+};
+
+pub fn n_fruit_effects() -> u8 {
+    snake_asset(SnakeAsset::FruitEffects).frames as u8
 }
 
+/// A new fruit spawned powered up, with its effect showing
+#[hyperfold_engine::event]
+struct PowerUp(pub Entity);
+
 #[hyperfold_engine::component]
 struct FruitEffect {
     pub fruit: Entity,
     pub img: u8,
 }
 
+/// `time_passed` is how far into its fade the effect starts, in ms
 pub fn new_fruit_effect(
     img: u8,
     fruit: Entity,
     pos: PointF,
+    time_passed: u32,
     entities: &mut dyn Components,
     r: &Renderer,
     am: &mut AssetManager,
 ) {
+    let sprite = SpriteAsset::frame(SnakeAsset::FruitEffects, img as u32);
+    let mut timer = Timer::new(2000);
+    timer.add_time(time_passed);
     let e = Entity::new();
     add_components!(
         entities,
         e,
         FruitEffect { fruit, img },
         Playing::Label,
-        Timer::new(2000),
+        timer,
         Elevation(Elevations::FruitEffect as u8),
-        RenderComponent::new(RenderAsset::from_file(&fruit_effect_image(img), r, am).with_alpha(0)),
+        RenderComponent::new(sprite.render_asset(r, am).with_alpha(0)),
+        sprite,
         Position(Rect::from(
             pos.x,
             pos.y,
@@ -94,9 +108,10 @@ fn update_fruit_effects(
             });
             if prev_over && timer.time_left() <= 200 {
                 new_fruit_effect(
-                    (effect.img + 1) % 3,
+                    (effect.img + 1) % n_fruit_effects(),
                     effect.fruit,
                     pos.0.center(),
+                    0,
                     entities,
                     r,
                     am,
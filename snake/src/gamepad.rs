@@ -0,0 +1,89 @@
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+};
+
+use input::{Binding, GamepadEvent, Gamepads, PadDirection};
+
+use crate::{
+    _engine::{Components, Events},
+    controls::{ControlsCS, SnakeAction, SnakeInput},
+};
+
+/// Snake is single player, the other slots are kept for multiplayer modes
+pub const MAX_PLAYERS: usize = 4;
+pub const SNAKE_PLAYER: usize = 0;
+
+#[hyperfold_engine::event]
+struct GamepadInput(pub GamepadEvent);
+
+#[hyperfold_engine::component(Singleton)]
+struct Controllers {
+    pub gamepads: Gamepads,
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_controllers(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Controllers {
+            gamepads: Gamepads::new(MAX_PLAYERS)
+        }
+    );
+}
+
+components!(ControllersCS, controllers: &'a mut Controllers);
+
+/// Controllers are polled rather than read from SDL events, which the engine consumes
+#[hyperfold_engine::system]
+fn poll_gamepads(
+    _: &Update,
+    ControllersCS { controllers, .. }: ControllersCS,
+    events: &mut dyn Events,
+) {
+    for event in controllers.gamepads.poll() {
+        match event {
+            GamepadEvent::Connected { player } => {
+                eprintln!("Controller connected for player {}", player + 1)
+            }
+            GamepadEvent::Disconnected { player } => {
+                eprintln!("Controller disconnected for player {}", player + 1)
+            }
+            _ => (),
+        }
+        events.new_event(GamepadInput(event));
+    }
+}
+
+/// Buttons go through the bindings, the stick always turns
+#[hyperfold_engine::system]
+fn gamepad_actions(
+    input: &GamepadInput,
+    ControlsCS { controls, .. }: ControlsCS,
+    events: &mut dyn Events,
+) {
+    if !controls.active {
+        return;
+    }
+    match input.0 {
+        GamepadEvent::Pressed {
+            player: SNAKE_PLAYER,
+            button,
+        } => controls.input(Binding::Button(button), events),
+        GamepadEvent::Direction {
+            player: SNAKE_PLAYER,
+            dir,
+        } if !controls.awaiting => {
+            events.new_event(SnakeInput(match dir {
+                PadDirection::Up => SnakeAction::TurnUp,
+                PadDirection::Down => SnakeAction::TurnDown,
+                PadDirection::Left => SnakeAction::TurnLeft,
+                PadDirection::Right => SnakeAction::TurnRight,
+            }));
+        }
+        _ => (),
+    }
+}
@@ -0,0 +1,439 @@
+use std::{cmp::Ordering, fs};
+
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::entities::{EntityTrash, NewEntity},
+    framework::{
+        physics::Position,
+        render_system::{
+            render_data::{RenderAsset, RenderDataBuilderTrait, RenderDataTrait},
+            AssetManager, Elevation, RenderComponent, Renderer,
+        },
+    },
+    utils::{
+        rect::{PointF, Rect},
+        util::AsType,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use assets::{
+    settings::{read_key_values, save_file},
+    strings::{tr, tr_with},
+};
+
+use crate::{
+    _engine::Components,
+    controls::ControlsCS,
+    elevations::Elevations,
+    fixed_step::{FixedStepCS, Motion, Tick},
+    fruit::{random_seed, EatFruit, FruitRng, FruitRngCS},
+    save_game::ResumeGame,
+    snake::{Direction, SnakeTrail, SnakeTurn, SNAKE_SPEED},
+    snake_asset,
+    snake_body::SNAKE_W,
+    trail::Trail,
+    GameOver, Playing, SnakeAsset,
+};
+
+/// The best run, played back as the ghost
+pub const BEST_RUN_FILE: &str = "save/snake_best_run.json";
+/// Whether to race the ghost
+pub const GHOST_FILE: &str = "save/snake_ghost.txt";
+/// Bump when the format changes, best runs from other versions are ignored
+pub const RUN_VERSION: u32 = 2;
+pub const GHOST_ALPHA: u8 = 96;
+
+/// Switches racing the ghost on or off from the next game
+#[hyperfold_engine::event]
+struct ToggleGhost;
+
+/// A run's inputs, enough to play it back on the same seed at the same tick rate
+/// Ticks are only counted while the snake moves, so pauses don't shift the playback
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Run {
+    pub version: u32,
+    pub seed: u64,
+    /// Ticks per second it was recorded at, see `--tick_hz`
+    pub hz: u32,
+    /// Distance the head moves each tick
+    pub step: f32,
+    /// Each turn with the ticks before it
+    pub turns: Vec<(u64, Direction)>,
+    /// Ticks before each fruit was eaten
+    pub eats: Vec<u64>,
+    /// Ticks survived
+    pub ticks: u64,
+}
+
+impl Run {
+    fn new(seed: u64, hz: u32, step: f32) -> Self {
+        Self {
+            version: RUN_VERSION,
+            seed,
+            hz,
+            step,
+            turns: Vec::new(),
+            eats: Vec::new(),
+            ticks: 0,
+        }
+    }
+
+    /// Seconds survived
+    pub fn survived_s(&self) -> f64 {
+        self.ticks as f64 / self.hz.max(1) as f64
+    }
+
+    /// More fruit, then longer survival, in seconds so runs at other tick rates compare fairly
+    pub fn beats(&self, other: &Self) -> bool {
+        match self.eats.len().cmp(&other.eats.len()) {
+            Ordering::Equal => self.survived_s() > other.survived_s(),
+            order => order == Ordering::Greater,
+        }
+    }
+
+    fn load() -> Option<Self> {
+        let text = fs::read_to_string(BEST_RUN_FILE).ok()?;
+        match serde_json::from_str::<Self>(&text) {
+            Ok(run) if run.version == RUN_VERSION => Some(run),
+            Ok(run) => {
+                eprintln!(
+                    "Ignoring best run {BEST_RUN_FILE}: version {} is not supported, expected {RUN_VERSION}",
+                    run.version
+                );
+                None
+            }
+            Err(e) => {
+                eprintln!("Could not read best run {BEST_RUN_FILE}: {e}");
+                None
+            }
+        }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(self) {
+            Ok(text) => save_file(BEST_RUN_FILE, "best run", text),
+            Err(e) => eprintln!("Could not save best run to {BEST_RUN_FILE}: {e}"),
+        }
+    }
+}
+
+/// The best run being played back, moved along its own trail like the snake
+pub struct Replay {
+    run: Run,
+    trail: Trail,
+    body_count: usize,
+    tick: u64,
+    next_turn: usize,
+    next_eat: usize,
+}
+
+impl Replay {
+    fn new(run: Run) -> Self {
+        Self {
+            run,
+            trail: Trail::new(PointF::new()),
+            body_count: 1,
+            tick: 0,
+            next_turn: 0,
+            next_eat: 0,
+        }
+    }
+
+    /// Plays one tick, returns false once the run is over
+    fn step(&mut self) -> bool {
+        if self.tick >= self.run.ticks {
+            return false;
+        }
+        while let Some(&(_, direction)) = self
+            .run
+            .turns
+            .get(self.next_turn)
+            .filter(|(tick, _)| *tick <= self.tick)
+        {
+            self.trail.turn(direction);
+            self.next_turn += 1;
+        }
+        self.trail.advance(self.run.step);
+        self.tick += 1;
+        while self
+            .run
+            .eats
+            .get(self.next_eat)
+            .is_some_and(|tick| *tick <= self.tick)
+        {
+            self.body_count += 1;
+            self.next_eat += 1;
+        }
+        self.trail.trim((self.body_count - 1) as f32 * SNAKE_W);
+        true
+    }
+}
+
+#[hyperfold_engine::component(Singleton)]
+struct GhostRace {
+    /// Race the best run from the next game
+    pub race: bool,
+    pub best: Option<Run>,
+    /// The run being played, None if it was resumed from a save
+    pub recording: Option<Run>,
+    pub replay: Option<Replay>,
+}
+
+impl GhostRace {
+    /// The best run if racing it, runs recorded at another tick rate can't be played back in step
+    pub fn raced(&self, hz: u32) -> Option<&Run> {
+        self.best.as_ref().filter(|run| self.race && run.hz == hz)
+    }
+
+    /// Reads a `race <on|off>` line, off without one
+    fn load() -> Self {
+        Self {
+            race: read_key_values(GHOST_FILE)
+                .iter()
+                .any(|(key, value)| key == "race" && value == "on"),
+            best: Run::load(),
+            recording: None,
+            replay: None,
+        }
+    }
+
+    fn save(&self) {
+        let text = format!("race {}\n", if self.race { "on" } else { "off" });
+        save_file(GHOST_FILE, "ghost setting", text);
+    }
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_ghost_race(entities: &mut dyn Components) {
+    let e = Entity::new();
+    add_components!(entities, e, GhostRace::load());
+}
+
+components!(GhostRaceCS, ghost: &'a mut GhostRace);
+
+/// One segment of the ghost, 0 is its head
+#[hyperfold_engine::component]
+struct GhostSegment {
+    pub idx: usize,
+    pub direction: Direction,
+}
+
+components!(
+    GhostSegments,
+    segment: &'a mut GhostSegment,
+    motion: &'a mut Motion,
+    tex: &'a mut RenderComponent
+);
+
+/// Drawn translucent with no hit box, so nothing collides with it
+fn add_ghost_segment(
+    idx: usize,
+    pos: PointF,
+    direction: Direction,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let asset = match idx {
+        0 => {
+            let entry = snake_asset(SnakeAsset::Snake);
+            entry.asset(r, am).with_animation(entry.animation())
+        }
+        _ => snake_asset(SnakeAsset::SnakeBody).frame_asset(0, r, am),
+    };
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Playing::Label,
+        GhostSegment { idx, direction },
+        Elevation(Elevations::Ghost as u8),
+        RenderComponent::new(
+            asset
+                .with_alpha(GHOST_ALPHA)
+                .with_rotation(direction.rotation(90.0), None)
+        ),
+        Position(Rect::from_center(pos.x, pos.y, SNAKE_W, SNAKE_W)),
+        Motion::new(pos)
+    );
+}
+
+/// Seeds the fruit, racing the best run uses its seed so fruit spawns the same way
+#[hyperfold_engine::system]
+fn start_run(
+    _: &Playing::OnEnter,
+    GhostRaceCS { ghost, .. }: GhostRaceCS,
+    FruitRngCS { rng, .. }: FruitRngCS,
+    FixedStepCS { step, .. }: FixedStepCS,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let best = ghost.raced(step.hz);
+    let seed = best.map_or_else(random_seed, |run| run.seed);
+    *rng = FruitRng::new(seed);
+    ghost.replay = best.cloned().map(Replay::new);
+    ghost.recording = Some(Run::new(seed, step.hz, SNAKE_SPEED * step.dt()));
+    if ghost.replay.is_some() {
+        add_ghost_segment(0, PointF::new(), Direction::Up, entities, r, am);
+    }
+}
+
+/// A resumed run can't be raced or recorded from the start
+#[hyperfold_engine::system]
+fn resume_without_ghost(
+    _: &ResumeGame,
+    GhostRaceCS { ghost, .. }: GhostRaceCS,
+    segments: Vec<GhostSegments>,
+    trash: &mut EntityTrash,
+) {
+    ghost.recording = None;
+    ghost.replay = None;
+    trash.0.extend(segments.into_iter().map(|s| *s.eid));
+}
+
+#[hyperfold_engine::system]
+fn record_turn(turn: &SnakeTurn, GhostRaceCS { ghost, .. }: GhostRaceCS) {
+    if let Some(run) = &mut ghost.recording {
+        run.turns.push((run.ticks, turn.0));
+    }
+}
+
+#[hyperfold_engine::system]
+fn record_eat(_: &EatFruit, GhostRaceCS { ghost, .. }: GhostRaceCS) {
+    if let Some(run) = &mut ghost.recording {
+        run.eats.push(run.ticks);
+    }
+}
+
+/// Counts the run's ticks and moves the ghost, which pauses with the snake
+#[hyperfold_engine::system]
+fn update_ghost(
+    _: &Tick,
+    GhostRaceCS { ghost, .. }: GhostRaceCS,
+    snake: SnakeTrail,
+    mut segments: Vec<GhostSegments>,
+    trash: &mut EntityTrash,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    for segment in segments.iter_mut() {
+        segment.motion.begin_tick();
+    }
+    if snake.snake.paused {
+        return;
+    }
+    if let Some(run) = &mut ghost.recording {
+        run.ticks += 1;
+    }
+
+    let Some(replay) = &mut ghost.replay else {
+        return;
+    };
+    // The best run ended here
+    if !replay.step() {
+        ghost.replay = None;
+        trash.0.extend(segments.into_iter().map(|s| *s.eid));
+        return;
+    }
+
+    let samples = replay.trail.sample(SNAKE_W, replay.body_count);
+    for GhostSegments {
+        segment,
+        motion,
+        tex,
+        ..
+    } in segments.iter_mut()
+    {
+        let Some(&(pos, direction)) = samples.get(segment.idx) else {
+            continue;
+        };
+        motion.pos = pos;
+        if direction != segment.direction {
+            segment.direction = direction;
+            tex.try_as_mut(|tex: &mut RenderAsset| {
+                tex.set_rotation(direction.rotation(90.0), None);
+            });
+        }
+    }
+    // The best run ate fruit
+    for (idx, &(pos, direction)) in samples.iter().enumerate().skip(segments.len()) {
+        add_ghost_segment(idx, pos, direction, entities, r, am);
+    }
+}
+
+/// Keeps the run if it beats the best one
+#[hyperfold_engine::system]
+fn finish_run(_: &GameOver::OnEnter, GhostRaceCS { ghost, .. }: GhostRaceCS) {
+    let Some(run) = ghost.recording.take() else {
+        return;
+    };
+    let better = match &ghost.best {
+        Some(best) => run.beats(best),
+        None => true,
+    };
+    if better {
+        run.save();
+        ghost.best = Some(run);
+    }
+}
+
+#[hyperfold_engine::system]
+fn toggle_ghost(
+    _: &ToggleGhost,
+    GhostRaceCS { ghost, .. }: GhostRaceCS,
+    ControlsCS { controls, .. }: ControlsCS,
+    FixedStepCS { step, .. }: FixedStepCS,
+) {
+    ghost.race = !ghost.race;
+    ghost.save();
+    controls.status = match (ghost.race, ghost.raced(step.hz).is_some()) {
+        (true, false) => tr("ghost_none"),
+        (race, _) => tr_with(
+            "ghost_race",
+            &[("value", &tr(if race { "access_on" } else { "access_off" }))],
+        ),
+    };
+    controls.dirty = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(hz: u32, eats: usize, ticks: u64) -> Run {
+        Run {
+            eats: vec![0; eats],
+            ticks,
+            ..Run::new(1, hz, SNAKE_SPEED / hz as f32)
+        }
+    }
+
+    #[test]
+    fn beats_compares_seconds() {
+        assert!(run(120, 2, 10).beats(&run(120, 1, 1000)));
+        assert!(run(120, 1, 240).beats(&run(120, 1, 239)));
+        assert!(!run(120, 1, 240).beats(&run(120, 1, 240)));
+        // 2 s at 60 Hz is shorter than 3 s at 120 Hz, though it is more ticks at 120 Hz
+        assert!(!run(60, 1, 120).beats(&run(120, 1, 360)));
+        assert!(run(60, 1, 200).beats(&run(120, 1, 360)));
+        assert!(run(240, 1, 721).beats(&run(120, 1, 360)));
+    }
+
+    #[test]
+    fn races_runs_at_the_same_rate() {
+        let mut ghost = GhostRace {
+            race: true,
+            best: Some(run(120, 1, 100)),
+            recording: None,
+            replay: None,
+        };
+        assert!(ghost.raced(120).is_some());
+        assert!(ghost.raced(60).is_none());
+        ghost.race = false;
+        assert!(ghost.raced(120).is_none());
+    }
+}
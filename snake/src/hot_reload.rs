@@ -0,0 +1,186 @@
+use std::path::Path;
+
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::render_system::{
+        render_data::{
+            Animation, RenderAsset, RenderDataBuilderTrait, RenderDataTrait, RenderTexture,
+        },
+        AssetManager, RenderComponent, Renderer,
+    },
+    utils::{
+        timer::{Timer, TimerTrait},
+        util::AsType,
+    },
+};
+
+use assets::{
+    hot_reload::{bump_version, FileWatcher},
+    strings, AssetKey, Piskel,
+};
+
+use crate::{
+    _engine::Components, controls::ControlsCS, game_over_texture, snake_asset, theme,
+    GameOverScreen, SnakeAsset,
+};
+
+pub const RES_DIR: &str = "res";
+pub const POLL_MS: u32 = 500;
+
+/// Which manifest asset an entity is drawn with, so it can be rebuilt on reload
+#[hyperfold_engine::component]
+struct SpriteAsset {
+    pub asset: SnakeAsset,
+    /// A single frame, otherwise the whole asset
+    pub frame: Option<u32>,
+    pub anim: Option<Animation>,
+}
+
+impl SpriteAsset {
+    pub fn new(asset: SnakeAsset) -> Self {
+        Self {
+            asset,
+            frame: None,
+            anim: None,
+        }
+    }
+
+    pub fn frame(asset: SnakeAsset, frame: u32) -> Self {
+        Self {
+            frame: Some(frame),
+            ..Self::new(asset)
+        }
+    }
+
+    pub fn animated(asset: SnakeAsset, anim: Animation) -> Self {
+        Self {
+            anim: Some(anim),
+            ..Self::new(asset)
+        }
+    }
+
+    pub fn render_asset(&self, r: &Renderer, am: &mut AssetManager) -> RenderAsset {
+        let entry = snake_asset(self.asset);
+        let asset = match self.frame {
+            Some(frame) => entry.frame_asset(frame, r, am),
+            None => entry.asset(r, am),
+        };
+        match self.anim {
+            Some(anim) => asset.with_animation(anim),
+            None => asset,
+        }
+    }
+}
+
+#[hyperfold_engine::component(Singleton)]
+struct ResWatcher {
+    pub watcher: FileWatcher,
+    pub timer: Timer,
+}
+
+/// Only watches `res/` in debug builds
+#[hyperfold_engine::system(Init)]
+fn new_res_watcher(entities: &mut dyn Components) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        ResWatcher {
+            watcher: FileWatcher::new(RES_DIR),
+            timer: Timer::new(POLL_MS)
+        }
+    );
+}
+
+components!(ResWatcherCS, watcher: &'a mut ResWatcher);
+
+components!(Sprites, sprite: &'a SpriteAsset, tex: &'a mut RenderComponent);
+
+components!(
+    labels(GameOverScreen),
+    GameOverTex,
+    tex: &'a mut RenderComponent
+);
+
+#[hyperfold_engine::system]
+fn hot_reload(
+    update: &Update,
+    watchers: Vec<ResWatcherCS>,
+    sprites: Vec<Sprites>,
+    game_over: Vec<GameOverTex>,
+    ControlsCS { controls, .. }: ControlsCS,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let Some(ResWatcherCS { watcher, .. }) = watchers.into_iter().next() else {
+        return;
+    };
+    if watcher.timer.add_time(update.0) == 0 {
+        return;
+    }
+
+    let changed = watcher.watcher.poll();
+    if changed.is_empty() {
+        return;
+    }
+    for path in changed.iter() {
+        bump_version(path);
+        if path.extension().is_some_and(|ext| ext == "piskel") {
+            match Piskel::reload(path) {
+                Ok(_) => eprintln!("Reloaded {}", path.display()),
+                Err(e) => eprintln!("Could not reload {}: {e}", path.display()),
+            }
+        }
+    }
+
+    // The board is redrawn with the new theme when the next game starts
+    let theme_changed = changed
+        .iter()
+        .any(|path| path.starts_with(theme::THEME_DIR));
+    if theme_changed {
+        theme::reload();
+    }
+
+    let is_changed = |path: &Path| changed.iter().any(|p| p == path);
+    let reloaded = SnakeAsset::ALL
+        .iter()
+        .copied()
+        .filter(|key| is_changed(&snake_asset(*key).path))
+        .collect::<Vec<_>>();
+    for Sprites { sprite, tex, .. } in sprites {
+        if !reloaded.contains(&sprite.asset) {
+            continue;
+        }
+        // Keep rotation, alpha, etc. from the old asset
+        let mut asset = sprite.render_asset(r, am);
+        tex.try_as(|ra: &RenderAsset| {
+            asset.set_render_options(ra.get_render_opts());
+            asset.set_dest(ra.get_dest_opts());
+        });
+        *tex = RenderComponent::new(asset);
+    }
+
+    let lang_changed = changed
+        .iter()
+        .any(|path| path.starts_with(strings::LANG_DIR));
+    if lang_changed {
+        strings::reload();
+    }
+    if lang_changed
+        || theme_changed
+        || changed
+            .iter()
+            .any(|path| path.extension().is_some_and(|ext| ext == "ttf"))
+    {
+        for GameOverTex { tex, .. } in game_over {
+            *tex =
+                RenderComponent::new(RenderTexture::new(Some(game_over_texture(controls, r, am))));
+        }
+    }
+}
@@ -3,38 +3,111 @@ use hyperfold_engine::{
     add_components, components,
     ecs::entities::NewEntity,
     framework::{
-        event_system::events::Key,
         physics::Position,
         render_system::{
             drawable::Canvas,
-            font::{FontData, TIMES},
+            font::FontData,
             render_data::{Fit, RenderDataBuilderTrait, RenderTexture},
             render_text::RenderText,
-            shapes::{Rectangle, ShapeTrait},
-            AssetManager, Camera, Elevation, RenderComponent, Renderer, Texture,
+            AssetManager, Elevation, RenderComponent, Renderer, Texture,
         },
     },
-    sdl2::{SDL_Color, SDL_KeyCode},
     utils::{
-        colors::{gray, WHITE},
+        colors::WHITE,
         rect::{Align, Point, PointF, Rect},
-        util::FloatMath,
     },
 };
 
-use crate::elevations::Elevations;
+use assets::{
+    accessibility::accessibility,
+    strings::{tr, tr_with},
+    AssetKey,
+};
+use grid::{grid_texture, GridLayout};
+
+use crate::{
+    camera::CameraEffects,
+    controls::{Controls, ControlsCS, SnakeAction, SnakeInput},
+    elevations::Elevations,
+    theme::{theme, ThemeAsset},
+};
 
+pub mod body_renderer;
+pub mod camera;
+pub mod collisions;
+pub mod controls;
+pub mod display;
 pub mod elevations;
+pub mod fixed_step;
 pub mod fruit;
 pub mod fruit_effect;
+pub mod gamepad;
+pub mod ghost;
+pub mod hot_reload;
+pub mod particle_effects;
+pub mod save_game;
 pub mod snake;
 pub mod snake_body;
 pub mod snake_death;
+pub mod sound;
+pub mod stats;
+pub mod theme;
+pub mod trail;
 
 use hyperfold_engine::system_macro;
 
 hyperfold_engine::game_crate!();
 
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum SnakeAsset {
+    Snake,
+    SnakeBody,
+    SnakeBodyCorner,
+    SnakeBodyTail,
+    SnakeDeath,
+    SnakeBodyDeath,
+    Fruit,
+    /// One frame per effect
+    FruitEffects,
+}
+
+impl AssetKey for SnakeAsset {
+    const ALL: &'static [Self] = &[
+        SnakeAsset::Snake,
+        SnakeAsset::SnakeBody,
+        SnakeAsset::SnakeBodyCorner,
+        SnakeAsset::SnakeBodyTail,
+        SnakeAsset::SnakeDeath,
+        SnakeAsset::SnakeBodyDeath,
+        SnakeAsset::Fruit,
+        SnakeAsset::FruitEffects,
+    ];
+
+    fn id(&self) -> &'static str {
+        match self {
+            SnakeAsset::Snake => "snake",
+            SnakeAsset::SnakeBody => "snake_body",
+            SnakeAsset::SnakeBodyCorner => "snake_body_corner",
+            SnakeAsset::SnakeBodyTail => "snake_body_tail",
+            SnakeAsset::SnakeDeath => "snake_death",
+            SnakeAsset::SnakeBodyDeath => "snake_body_death",
+            SnakeAsset::Fruit => "fruit",
+            SnakeAsset::FruitEffects => "fruit_effects",
+        }
+    }
+}
+
+/// From the current theme's manifest, see `theme()` for a theme that can't be loaded
+pub fn snake_asset(key: SnakeAsset) -> ThemeAsset {
+    ThemeAsset::new(theme(), key)
+}
+
+/// Loads the theme, checking every asset up front instead of when a system first draws it
+/// Panics listing every missing or invalid asset, call it only when starting snake
+pub fn validate_assets() {
+    theme();
+}
+
 pub const SQUARE_W: u32 = 50;
 pub const N_SQUARES: u32 = 10;
 
@@ -42,18 +115,16 @@ pub const W_I: u32 = SQUARE_W * N_SQUARES;
 pub const W_F: f32 = W_I as f32;
 pub const HALF_W: f32 = W_F / 2.0;
 
-pub fn pos_to_square(pos: PointF, camera: &Camera) -> Point {
-    Point {
-        x: (pos.x - camera.0.cx() + W_F / 2.0).round_i32() / SQUARE_W as i32,
-        y: (pos.y - camera.0.cy() + W_F / 2.0).round_i32() / SQUARE_W as i32,
-    }
+pub const GRID: GridLayout = GridLayout::new(SQUARE_W as f32, N_SQUARES, N_SQUARES);
+
+/// Uses the camera without shake or zoom so cells don't move with the effects
+/// Floors into the square containing `pos`, so anything off the board is outside it
+pub fn pos_to_square(pos: PointF, camera: &CameraEffects) -> Point {
+    GRID.world_to_cell(pos, &camera.base_camera())
 }
 
-pub fn square_to_pos(pos: Point, camera: &Camera) -> PointF {
-    PointF {
-        x: SQUARE_W as f32 * (pos.x as f32 + 0.5) + camera.0.cx() - W_F / 2.0,
-        y: SQUARE_W as f32 * (pos.y as f32 + 0.5) + camera.0.cy() - W_F / 2.0,
-    }
+pub fn square_to_pos(pos: Point, camera: &CameraEffects) -> PointF {
+    GRID.cell_to_world(pos, &camera.base_camera())
 }
 
 #[hyperfold_engine::state]
@@ -63,29 +134,50 @@ struct Playing;
 struct GameOver;
 
 #[hyperfold_engine::component(Singleton)]
-struct Background;
-
-#[hyperfold_engine::system(Init)]
-fn create_bkgrnd(entities: &mut dyn _engine::Components, r: &Renderer) {
-    let tex = Texture::new(r, W_I, W_I, gray(100));
-    let w = SQUARE_W as f32;
-    for x in (0..N_SQUARES).map(|x| x as f32 * w) {
-        for y in (0..N_SQUARES).map(|y| y as f32 * w) {
-            tex.draw(
+struct Background {
+    /// Theme and high contrast setting the board was drawn with
+    pub theme: String,
+    pub high_contrast: bool,
+}
+
+components!(
+    Backgrounds,
+    bkgrnd: &'a mut Background,
+    tex: &'a mut RenderComponent
+);
+
+#[hyperfold_engine::system]
+fn create_bkgrnd(
+    _: &Playing::OnEnter,
+    bkgrnd: Vec<Backgrounds>,
+    entities: &mut dyn _engine::Components,
+    r: &Renderer,
+) {
+    let theme = theme();
+    let high_contrast = accessibility().high_contrast;
+    // Only the game that is started creates its background, later games redraw it if the style changed
+    if let Some(Backgrounds { bkgrnd, tex, .. }) = bkgrnd.into_iter().next() {
+        if bkgrnd.theme != theme.id || bkgrnd.high_contrast != high_contrast {
+            bkgrnd.theme = theme.id.clone();
+            bkgrnd.high_contrast = high_contrast;
+            *tex = RenderComponent::new(RenderTexture::new(Some(grid_texture(
+                &GRID,
+                &theme.grid(),
                 r,
-                &mut Rectangle::new().set_color(gray(200)).border(
-                    Rect { x, y, w, h: w },
-                    -2.0,
-                    false,
-                ),
-            );
+            ))));
         }
+        return;
     }
+
+    let tex = grid_texture(&GRID, &theme.grid(), r);
     let e = Entity::new();
     add_components!(
         entities,
         e,
-        Background,
+        Background {
+            theme: theme.id.clone(),
+            high_contrast
+        },
         Elevation(Elevations::Background as u8),
         RenderComponent::new(RenderTexture::new(Some(tex))),
         Position(Rect::from(0.0, 0.0, W_F, W_F, Align::Center, Align::Center))
@@ -97,35 +189,27 @@ struct GameOverScreen;
 
 components!(labels(GameOverScreen), GameOverEids);
 
-#[hyperfold_engine::system]
-fn game_over(
-    _: &GameOver::OnEnter,
-    entities: &mut dyn _engine::Components,
-    r: &Renderer,
-    am: &mut AssetManager,
-) {
-    let tex = Texture::new(
-        r,
-        W_I,
-        W_I,
-        SDL_Color {
-            r: 0,
-            g: 0,
-            b: 0,
-            a: 64,
-        },
-    );
+/// A text size scaled by the accessibility setting
+pub fn scaled_text(size: u32) -> u32 {
+    (size as f32 * accessibility().text_scale).round() as u32
+}
+
+/// Separate from `game_over()` so the screen can be redrawn when its font is reloaded
+pub fn game_over_texture(controls: &Controls, r: &Renderer, am: &mut AssetManager) -> Texture {
+    let theme = theme();
+    let tex = Texture::new(r, W_I, W_I, theme.overlay());
 
-    // Game over text
+    // Game over text, fonts are sized to the translated text
     let rect = Rect::from_center(HALF_W, HALF_W, 0.0, 0.0);
+    let text = tr("game_over");
     let mut font = FontData {
-        w: Some(W_I / 3),
+        w: Some(scaled_text(W_I / 3)),
         h: None,
-        sample: "Game Over!".to_string(),
-        file: TIMES.to_string(),
+        sample: text.clone(),
+        file: theme.font(),
     };
     let mut rt = RenderText::new(font.clone())
-        .with_text("Game Over!")
+        .with_text(&text)
         .with_text_color(WHITE)
         .with_dest_align(Align::Center, Align::BotRight)
         .with_dest_fit(Fit::None)
@@ -133,16 +217,35 @@ fn game_over(
     rt.render_text(rect, r, am);
     tex.draw(r, &mut rt);
 
-    // Press 'r' text
-    font.w = Some(W_I / 2);
-    font.sample = "Press 'r' to restart".to_string();
+    // Restart hint
+    let text = tr_with(
+        "restart_hint",
+        &[
+            ("restart", &controls.bindings.hint(SnakeAction::Restart)),
+            ("menu", &controls.bindings.hint(SnakeAction::Menu)),
+            ("stats", &controls.bindings.hint(SnakeAction::Stats)),
+        ],
+    );
+    font.w = Some(scaled_text(W_I * 3 / 4).min(W_I));
+    font.sample = text.clone();
     let mut rt = rt
         .with_font_data(font)
-        .with_text("Press 'r' to restart")
+        .with_text(&text)
         .with_dest_align(Align::Center, Align::TopLeft);
     rt.render_text(rect, r, am);
     tex.draw(r, &mut rt);
+    tex
+}
 
+#[hyperfold_engine::system]
+fn game_over(
+    _: &GameOver::OnEnter,
+    ControlsCS { controls, .. }: ControlsCS,
+    entities: &mut dyn _engine::Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let tex = game_over_texture(controls, r, am);
     let e = Entity::new();
     add_components!(
         entities,
@@ -159,8 +262,48 @@ fn game_over(
 #[hyperfold_engine::system {
     
 }]
-fn restart(key: &Key, game_over: Vec<GameOverEids>, events: &mut dyn _engine::Events) {
-    if !game_over.is_empty() && matches!(key.0.key, SDL_KeyCode::SDLK_r) {
+fn restart(input: &SnakeInput, game_over: Vec<GameOverEids>, events: &mut dyn _engine::Events) {
+    if !game_over.is_empty() && input.0 == SnakeAction::Restart {
         events.set_state(Playing::Data);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera() -> CameraEffects {
+        CameraEffects {
+            base: Rect::from_center(0.0, 0.0, W_F, W_F),
+            trauma: 1.0,
+            zoom: None,
+            active: true,
+        }
+    }
+
+    fn square(x: f32, y: f32) -> (i32, i32) {
+        let p = pos_to_square(PointF { x, y }, &camera());
+        (p.x, p.y)
+    }
+
+    #[test]
+    fn pos_to_square_floors() {
+        let edge = -HALF_W;
+        assert_eq!(square(edge, edge), (0, 0));
+        assert_eq!(square(edge + 49.9, edge + 25.0), (0, 0));
+        assert_eq!(square(edge + 50.0, edge), (1, 0));
+        assert_eq!(square(0.0, 0.0), (5, 5));
+        assert_eq!(square(-0.1, -0.1), (4, 4));
+        // Just off the board is outside it rather than rounded onto the edge squares
+        assert_eq!(square(edge - 0.1, edge - 10.0), (-1, -1));
+        assert_eq!(square(-edge, -edge - 0.1), (N_SQUARES as i32, N_SQUARES as i32 - 1));
+    }
+
+    #[test]
+    fn square_centers_map_back() {
+        for (x, y) in [(0, 0), (3, 7), (9, 9)] {
+            let pos = square_to_pos(Point { x, y }, &camera());
+            assert_eq!(square(pos.x, pos.y), (x, y));
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::{entities::NewEntity, events::core::Update},
+    framework::{
+        physics::Position,
+        render_system::{
+            render_data::{RenderAsset, RenderDataBuilderTrait},
+            AssetManager, Elevation, RenderComponent, Renderer,
+        },
+    },
+    sdl2::SDL_Color,
+    utils::{
+        rect::{PointF, Rect},
+        util::AsType,
+    },
+};
+
+use particles::{particle_texture, EmitterConfig, ParticlePool};
+
+use crate::{
+    _engine::Components,
+    elevations::Elevations,
+    fruit::{EatFruit, FruitPos},
+    snake_body::SnakeBodyPos,
+    stats::Died,
+    Playing,
+};
+
+pub const EAT_BURST: usize = 24;
+pub const DEATH_BURST_PER_BODY: usize = 8;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum ParticleEffect {
+    Eat,
+    Death,
+}
+
+impl ParticleEffect {
+    pub const ALL: [ParticleEffect; 2] = [ParticleEffect::Eat, ParticleEffect::Death];
+
+    /// Number of pooled particles
+    pub fn capacity(&self) -> usize {
+        match self {
+            ParticleEffect::Eat => 64,
+            ParticleEffect::Death => 256,
+        }
+    }
+
+    pub fn config(&self) -> EmitterConfig {
+        match self {
+            ParticleEffect::Eat => EmitterConfig {
+                lifetime_ms: (300, 600),
+                speed: (80.0, 220.0),
+                size: (10.0, 2.0),
+                color: (
+                    SDL_Color {
+                        r: 255,
+                        g: 230,
+                        b: 80,
+                        a: 255,
+                    },
+                    SDL_Color {
+                        r: 230,
+                        g: 40,
+                        b: 40,
+                        a: 0,
+                    },
+                ),
+                ..EmitterConfig::default()
+            },
+            ParticleEffect::Death => EmitterConfig {
+                lifetime_ms: (600, 1200),
+                speed: (40.0, 160.0),
+                angle: 270.0,
+                spread: 70.0,
+                gravity: PointF { x: 0.0, y: 300.0 },
+                size: (12.0, 4.0),
+                color: (
+                    SDL_Color {
+                        r: 120,
+                        g: 220,
+                        b: 60,
+                        a: 255,
+                    },
+                    SDL_Color {
+                        r: 40,
+                        g: 70,
+                        b: 30,
+                        a: 0,
+                    },
+                ),
+                ..EmitterConfig::default()
+            },
+        }
+    }
+}
+
+#[hyperfold_engine::component]
+struct ParticleEmitter {
+    pub effect: ParticleEffect,
+    pub config: EmitterConfig,
+    pub pool: ParticlePool,
+    /// Where particles are continuously emitted from, None to only burst
+    pub origin: Option<PointF>,
+}
+
+/// One per pool slot, created with its emitter and reused by every particle in that slot
+#[hyperfold_engine::component]
+struct ParticleSprite {
+    pub emitter: Entity,
+    pub slot: usize,
+    /// Color step currently drawn, None while the slot is free
+    pub step: Option<u8>,
+}
+
+fn particle_asset(
+    config: &EmitterConfig,
+    step: u8,
+    r: &Renderer,
+    am: &mut AssetManager,
+) -> RenderAsset {
+    let color = config.step_color(step);
+    RenderAsset::from_file(&particle_texture(config.texture.as_deref(), color), r, am)
+        .with_alpha(color.a)
+}
+
+/// Creates the emitter and every particle entity it will ever use
+pub fn new_emitter(
+    effect: ParticleEffect,
+    origin: Option<PointF>,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) -> Entity {
+    let config = effect.config();
+    let pool = ParticlePool::new(effect.capacity());
+    let emitter = Entity::new();
+    for slot in 0..pool.capacity() {
+        let e = Entity::new();
+        add_components!(
+            entities,
+            e,
+            ParticleSprite {
+                emitter,
+                slot,
+                step: None
+            },
+            Elevation(Elevations::Particles as u8),
+            RenderComponent::new(particle_asset(&config, 0, r, am).with_alpha(0)),
+            Position(Rect::from_center(0.0, 0.0, 0.0, 0.0))
+        );
+    }
+    add_components!(
+        entities,
+        emitter,
+        ParticleEmitter {
+            effect,
+            config,
+            pool,
+            origin
+        }
+    );
+    emitter
+}
+
+components!(Emitters, emitter: &'a mut ParticleEmitter);
+
+/// Emitters outlive games so death bursts keep playing on the game over screen
+#[hyperfold_engine::system]
+fn new_emitters(
+    _: &Playing::OnEnter,
+    emitters: Vec<Emitters>,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    if !emitters.is_empty() {
+        return;
+    }
+    for effect in ParticleEffect::ALL {
+        new_emitter(effect, None, entities, r, am);
+    }
+}
+
+fn burst(emitters: Vec<Emitters>, effect: ParticleEffect, n: usize, origins: &[PointF]) {
+    for Emitters { emitter, .. } in emitters {
+        if emitter.effect == effect {
+            for origin in origins {
+                emitter.pool.burst(n, *origin, &emitter.config);
+            }
+        }
+    }
+}
+
+#[hyperfold_engine::system]
+fn eat_particles(eat: &EatFruit, fruits: Vec<FruitPos>, emitters: Vec<Emitters>) {
+    if let Some(fruit) = fruits.iter().find(|fruit| *fruit.eid == eat.0) {
+        burst(
+            emitters,
+            ParticleEffect::Eat,
+            EAT_BURST,
+            &[fruit.pos.0.center()],
+        );
+    }
+}
+
+#[hyperfold_engine::system]
+fn death_particles(_: &Died, bodies: Vec<SnakeBodyPos>, emitters: Vec<Emitters>) {
+    let origins = bodies
+        .iter()
+        .map(|body| body.pos.0.center())
+        .collect::<Vec<_>>();
+    burst(
+        emitters,
+        ParticleEffect::Death,
+        DEATH_BURST_PER_BODY,
+        &origins,
+    );
+}
+
+components!(
+    ParticleSprites,
+    sprite: &'a mut ParticleSprite,
+    pos: &'a mut Position,
+    tex: &'a mut RenderComponent
+);
+
+#[hyperfold_engine::system]
+fn update_particles(
+    update: &Update,
+    emitters: Vec<Emitters>,
+    sprites: Vec<ParticleSprites>,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let mut pools = HashMap::new();
+    for Emitters { eid, emitter } in emitters {
+        if let Some(origin) = emitter.origin {
+            emitter.pool.emit(update.0, origin, &emitter.config);
+        }
+        emitter.pool.update(update.0, &emitter.config);
+        pools.insert(*eid, &*emitter);
+    }
+
+    for ParticleSprites {
+        sprite, pos, tex, ..
+    } in sprites
+    {
+        let Some(emitter) = pools.get(&sprite.emitter) else {
+            continue;
+        };
+        match emitter.pool.get(sprite.slot) {
+            Some(particle) => {
+                let step = particle.color_step();
+                if sprite.step != Some(step) {
+                    // Textures are shared by path, so this doesn't load a new image
+                    *tex = RenderComponent::new(particle_asset(&emitter.config, step, r, am));
+                    sprite.step = Some(step);
+                }
+                pos.0 = particle.rect(&emitter.config);
+            }
+            None if sprite.step.is_some() => {
+                tex.try_as_mut(|tex: &mut RenderAsset| tex.set_alpha(0));
+                sprite.step = None;
+            }
+            None => (),
+        }
+    }
+}
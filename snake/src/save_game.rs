@@ -0,0 +1,444 @@
+use std::{fs, io::ErrorKind};
+
+use hyperfold_engine::{
+    components,
+    framework::render_system::{AssetManager, Renderer},
+    utils::{
+        rect::PointF,
+        timer::{Timer, TimerTrait},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use assets::{accessibility::accessibility, settings::save_file};
+
+use crate::{
+    _engine::{Components, Events},
+    collisions::CollisionsCS,
+    fixed_step::{FixedStepCS, Motion, Tick},
+    fruit::{add_fruit, FruitPos, FruitRng, FruitRngCS, SpawnFruit},
+    fruit_effect::{new_fruit_effect, FruitEffect},
+    snake::{Direction, Snake, SnakeTrail},
+    snake_body::{add_snake_body, SnakeBody},
+    stats::{GameMode, StatsCS},
+    trail::Trail,
+    GameOver,
+};
+
+/// The run in progress, removed when it ends
+pub const SAVE_FILE: &str = "save/snake_game.json";
+/// Bump when the format changes, saves from other versions are discarded
+pub const SAVE_VERSION: u32 = 4;
+/// Seconds between saves while playing
+/// The engine has no event for the window closing, so closing it loses up to this much play
+pub const AUTOSAVE_S: f32 = 1.0;
+
+/// Saves the run in progress
+#[hyperfold_engine::event]
+struct SaveGame;
+
+/// Fired instead of spawning fruit when a saved run is loaded, the snake is already restored
+#[hyperfold_engine::event]
+struct ResumeGame(pub SavedGame);
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedSegment {
+    pub snake_idx: usize,
+    pub direction: Direction,
+    pub pos: [f32; 2],
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedEffect {
+    pub img: u8,
+    /// Time into the effect's fade in ms
+    pub time_passed: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedFruit {
+    pub pos: [f32; 2],
+    pub effects: Vec<SavedEffect>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub tick: u64,
+    /// The fruit RNG, so fruit keeps spawning where it would have
+    pub seed: u64,
+    pub rng_state: u64,
+    pub speed: f32,
+    pub paused: bool,
+    pub body_count: usize,
+    /// The head, every corner and the end of the trail
+    pub trail: Vec<[f32; 2]>,
+    /// None if the snake hasn't moved yet
+    pub heading: Option<Direction>,
+    /// The head is segment 0 once it is moving
+    pub segments: Vec<SavedSegment>,
+    pub fruit: Vec<SavedFruit>,
+    /// Seconds the snake has moved, for the survival stat
+    pub survived_s: f32,
+    /// Played time keeps counting towards the mode the run started in
+    pub mode: GameMode,
+}
+
+fn point([x, y]: [f32; 2]) -> PointF {
+    PointF { x, y }
+}
+
+fn array(p: PointF) -> [f32; 2] {
+    [p.x, p.y]
+}
+
+impl SavedGame {
+    /// Checks the version before anything else so older formats get a clear message
+    fn parse(text: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        match value.get("version").and_then(|v| v.as_u64()) {
+            Some(version) if version == SAVE_VERSION as u64 => (),
+            Some(version) => {
+                return Err(format!(
+                    "Version {version} is not supported, expected {SAVE_VERSION}"
+                ))
+            }
+            None => return Err("Missing version".to_string()),
+        }
+        let save: Self = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        if save.trail().is_none() {
+            return Err("Invalid trail".to_string());
+        }
+        if !save.segments_match() {
+            return Err("Segments don't match the body count".to_string());
+        }
+        if !save.speed.is_finite() {
+            return Err("Invalid speed".to_string());
+        }
+        if !save.survived_s.is_finite() {
+            return Err("Invalid survival time".to_string());
+        }
+        Ok(save)
+    }
+
+    /// None if there are no points or any isn't finite
+    fn trail(&self) -> Option<Trail> {
+        let finite = self.trail.iter().flatten().all(|v| v.is_finite());
+        Trail::from_points(self.trail.iter().copied().map(point), self.heading).filter(|_| finite)
+    }
+
+    /// Exactly one segment for each body index, the head's is only there once it has moved
+    fn segments_match(&self) -> bool {
+        let mut seen = vec![false; self.body_count];
+        for segment in self.segments.iter() {
+            if !segment.pos.iter().all(|v| v.is_finite()) {
+                return false;
+            }
+            match seen.get_mut(segment.snake_idx) {
+                Some(seen @ false) => *seen = true,
+                _ => return false,
+            }
+        }
+        self.body_count > 0 && seen.iter().skip(1).all(|seen| *seen)
+    }
+
+    /// Only for saves from `load_game`, which has checked the trail
+    pub fn snake(&self) -> Snake {
+        Snake {
+            body_count: self.body_count,
+            trail: self.trail().expect("Saved trail is checked when loaded"),
+            paused: self.paused,
+        }
+    }
+}
+
+fn discard_save() {
+    match fs::remove_file(SAVE_FILE) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            eprintln!("Could not remove saved game {SAVE_FILE}: {e}")
+        }
+        _ => (),
+    }
+}
+
+/// The saved run, if there is one, saves that can't be read are discarded
+pub fn load_game() -> Option<SavedGame> {
+    let text = fs::read_to_string(SAVE_FILE).ok()?;
+    match SavedGame::parse(&text) {
+        Ok(save) => Some(save),
+        Err(e) => {
+            eprintln!("Discarding saved game {SAVE_FILE}: {e}");
+            discard_save();
+            None
+        }
+    }
+}
+
+components!(SavedBodies, body: &'a SnakeBody, motion: &'a Motion);
+
+components!(SavedEffects, effect: &'a FruitEffect, timer: &'a Timer);
+
+#[hyperfold_engine::system]
+fn save_game(
+    _: &SaveGame,
+    snake: SnakeTrail,
+    bodies: Vec<SavedBodies>,
+    fruits: Vec<FruitPos>,
+    effects: Vec<SavedEffects>,
+    FixedStepCS { step, .. }: FixedStepCS,
+    FruitRngCS { rng, .. }: FruitRngCS,
+    StatsCS { stats, .. }: StatsCS,
+) {
+    let save = SavedGame {
+        version: SAVE_VERSION,
+        tick: step.tick,
+        seed: rng.seed,
+        rng_state: rng.state,
+        speed: snake.speed.0,
+        paused: snake.snake.paused,
+        body_count: snake.snake.body_count,
+        trail: snake.snake.trail.points().map(array).collect(),
+        heading: snake.snake.trail.heading(),
+        segments: bodies
+            .iter()
+            .map(|body| SavedSegment {
+                snake_idx: body.body.snake_idx,
+                direction: body.body.direction,
+                pos: array(body.motion.pos),
+            })
+            .collect(),
+        fruit: fruits
+            .iter()
+            .map(|fruit| SavedFruit {
+                pos: array(fruit.pos.0.center()),
+                effects: effects
+                    .iter()
+                    .filter(|effect| effect.effect.fruit == *fruit.eid)
+                    .map(|effect| SavedEffect {
+                        img: effect.effect.img,
+                        time_passed: effect.timer.time_passed(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        survived_s: stats.survived_s,
+        mode: stats.mode,
+    };
+
+    match serde_json::to_string(&save) {
+        Ok(text) => save_file(SAVE_FILE, "game", text),
+        Err(e) => eprintln!("Could not save game to {SAVE_FILE}: {e}"),
+    }
+}
+
+/// Saves every `AUTOSAVE_S` while the snake moves, pausing saves as well
+#[hyperfold_engine::system]
+fn autosave(tick: &Tick, snake: SnakeTrail, events: &mut dyn Events) {
+    let every = (AUTOSAVE_S / tick.dt).round().max(1.0) as u64;
+    if !snake.snake.paused && tick.tick > 0 && tick.tick % every == 0 {
+        events.new_event(SaveGame);
+    }
+}
+
+/// Restores the bodies, fruit, tick count, RNG, mode and survival time, `new_snake` has already
+/// restored the head
+#[hyperfold_engine::system]
+fn resume_game(
+    resume: &ResumeGame,
+    FixedStepCS { step, .. }: FixedStepCS,
+    FruitRngCS { rng, .. }: FruitRngCS,
+    CollisionsCS { collisions, .. }: CollisionsCS,
+    StatsCS { stats, .. }: StatsCS,
+    entities: &mut dyn Components,
+    events: &mut dyn Events,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let save = &resume.0;
+    step.tick = save.tick;
+    *rng = FruitRng {
+        seed: save.seed,
+        state: save.rng_state,
+    };
+    // The ghost isn't raced again, see `resume_without_ghost`, but the run is still timed as one
+    stats.mode = save.mode;
+    stats.survived_s = save.survived_s;
+    for segment in save.segments.iter().filter(|s| s.snake_idx > 0) {
+        add_snake_body(
+            point(segment.pos),
+            segment.direction,
+            segment.snake_idx,
+            collisions,
+            entities,
+        );
+    }
+    // Fruit effects flash so they are left out with reduced motion
+    let reduced_motion = accessibility().reduced_motion;
+    for fruit in save.fruit.iter() {
+        let pos = point(fruit.pos);
+        let e = add_fruit(pos, collisions, entities, r, am);
+        for effect in fruit.effects.iter().filter(|_| !reduced_motion) {
+            new_fruit_effect(effect.img, e, pos, effect.time_passed, entities, r, am);
+        }
+    }
+    if save.fruit.is_empty() {
+        events.new_event(SpawnFruit);
+    }
+}
+
+/// A finished run can't be resumed
+#[hyperfold_engine::system]
+fn end_save(_: &GameOver::OnEnter) {
+    discard_save();
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn save() -> Value {
+        let save = SavedGame {
+            version: SAVE_VERSION,
+            tick: 600,
+            seed: 7,
+            rng_state: 11,
+            speed: 100.0,
+            paused: false,
+            body_count: 2,
+            trail: vec![[0.0, -50.0], [0.0, 0.0]],
+            heading: Some(Direction::Up),
+            segments: vec![
+                SavedSegment {
+                    snake_idx: 0,
+                    direction: Direction::Up,
+                    pos: [0.0, -50.0],
+                },
+                SavedSegment {
+                    snake_idx: 1,
+                    direction: Direction::Up,
+                    pos: [0.0, 0.0],
+                },
+            ],
+            fruit: vec![SavedFruit {
+                pos: [100.0, 100.0],
+                effects: vec![SavedEffect {
+                    img: 1,
+                    time_passed: 250,
+                }],
+            }],
+            survived_s: 5.0,
+            mode: GameMode::Ghost,
+        };
+        serde_json::to_value(save).unwrap()
+    }
+
+    fn error(value: Value) -> Option<String> {
+        SavedGame::parse(&value.to_string()).err()
+    }
+
+    #[test]
+    fn parses_a_save() {
+        let save = SavedGame::parse(&save().to_string()).unwrap();
+        assert_eq!(save.body_count, 2);
+        assert_eq!(save.trail, [[0.0, -50.0], [0.0, 0.0]]);
+        assert!(save.mode == GameMode::Ghost);
+        assert!(save.heading == Some(Direction::Up));
+        assert_eq!(save.fruit[0].effects[0].time_passed, 250);
+    }
+
+    #[test]
+    fn checks_the_version_first() {
+        let mut value = save();
+        value["version"] = json!(SAVE_VERSION - 1);
+        // Older saves are reported by version even though their other fields don't match
+        value["mode"] = Value::Null;
+        assert_eq!(
+            error(value),
+            Some(format!(
+                "Version {} is not supported, expected {SAVE_VERSION}",
+                SAVE_VERSION - 1
+            ))
+        );
+
+        let mut value = save();
+        value.as_object_mut().unwrap().remove("version");
+        assert_eq!(error(value).as_deref(), Some("Missing version"));
+
+        let mut value = save();
+        value["version"] = json!("4");
+        assert_eq!(error(value).as_deref(), Some("Missing version"));
+
+        assert!(SavedGame::parse("not json").is_err());
+        assert!(SavedGame::parse("[]").is_err());
+    }
+
+    fn segment(snake_idx: usize) -> Value {
+        json!({ "snake_idx": snake_idx, "direction": "Up", "pos": [0.0, 0.0] })
+    }
+
+    #[test]
+    fn checks_the_body_count() {
+        let mismatch = Some("Segments don't match the body count");
+        let with = |body_count: usize, segments: Vec<Value>| {
+            let mut value = save();
+            value["body_count"] = json!(body_count);
+            value["segments"] = json!(segments);
+            error(value)
+        };
+        // The head has no segment until it moves, when it is still the only one
+        assert_eq!(with(1, vec![]), None);
+        assert_eq!(with(1, vec![segment(0)]), None);
+        assert_eq!(with(3, vec![segment(0), segment(2), segment(1)]), None);
+
+        assert_eq!(with(0, vec![]).as_deref(), mismatch);
+        // Missing, duplicated and extra segments
+        assert_eq!(with(2, vec![]).as_deref(), mismatch);
+        assert_eq!(with(3, vec![segment(0), segment(1)]).as_deref(), mismatch);
+        assert_eq!(with(2, vec![segment(1), segment(1)]).as_deref(), mismatch);
+        assert_eq!(
+            with(2, vec![segment(0), segment(0), segment(1)]).as_deref(),
+            mismatch
+        );
+        assert_eq!(with(1, vec![segment(0), segment(1)]).as_deref(), mismatch);
+
+        let mut bad = segment(1);
+        bad["pos"] = json!([0.0, 1e39]);
+        assert_eq!(with(2, vec![segment(0), bad]).as_deref(), mismatch);
+    }
+
+    #[test]
+    fn checks_the_trail() {
+        let mut value = save();
+        value["trail"] = json!([]);
+        assert_eq!(error(value).as_deref(), Some("Invalid trail"));
+
+        let mut value = save();
+        value["trail"] = json!([[0.0, 0.0], [1e39, 0.0]]);
+        assert_eq!(error(value).as_deref(), Some("Invalid trail"));
+
+        let mut value = save();
+        value["trail"] = json!([[0.0, 0.0]]);
+        value["heading"] = Value::Null;
+        let save = SavedGame::parse(&value.to_string()).unwrap();
+        assert_eq!(save.snake().trail.points().count(), 1);
+    }
+
+    #[test]
+    fn checks_numbers_are_finite() {
+        // Too large for an f32, it parses as infinity
+        let mut value = save();
+        value["speed"] = json!(1e39);
+        assert_eq!(error(value).as_deref(), Some("Invalid speed"));
+
+        let mut value = save();
+        value["survived_s"] = json!(-1e39);
+        assert_eq!(error(value).as_deref(), Some("Invalid survival time"));
+
+        // NaN is written as null, which doesn't parse at all
+        let mut value = save();
+        value["speed"] = Value::Null;
+        assert!(error(value).is_some());
+    }
+}
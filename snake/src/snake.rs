@@ -1,33 +1,38 @@
-use std::collections::VecDeque;
-
 use hyperfold_engine::{
     _engine::Entity,
     add_components, components,
-    ecs::{entities::NewEntity, events::core::Update},
+    ecs::entities::NewEntity,
     framework::{
-        event_system::events::Key,
-        physics::{BoundaryCollision, HitBox, PhysicsData, Position},
+        physics::{HitBox, Position},
         render_system::{
-            render_data::{Animation, RenderAsset, RenderDataBuilderTrait},
-            AssetManager, Camera, Elevation, RenderComponent, Renderer,
+            render_data::RenderDataBuilderTrait, AssetManager, Elevation, RenderComponent, Renderer,
         },
     },
-    sdl2::SDL_KeyCode,
     utils::{
-        rect::{Align, PointF, Rect},
+        rect::{PointF, Rect},
         timer::{Timer, TimerTrait},
     },
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     _engine::{Components, Events},
+    collisions::{Collider, CollisionsCS},
+    controls::{SnakeAction, SnakeInput},
     elevations::Elevations,
+    fixed_step::{Motion, Tick},
     fruit::SpawnFruit,
-    snake_body::{SnakeBody, SnakeBodyAnim, SnakeBodyPos, SNAKE_HB_W, SNAKE_W},
-    GameOver, Playing, W_F,
+    hot_reload::SpriteAsset,
+    save_game::{load_game, ResumeGame, SaveGame},
+    snake_asset,
+    snake_body::{SnakeBody, SnakeBodyAnim, SNAKE_HB_W, SNAKE_W},
+    sound::{PlaySound, SnakeSound},
+    stats::{DeathCause, Died},
+    trail::Trail,
+    GameOver, Playing, SnakeAsset,
 };
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
@@ -46,6 +51,41 @@ impl Direction {
         PointF { x, y }
     }
 
+    /// Direction of the larger component of `v`, None if `v` is zero
+    pub fn from_vector(v: PointF) -> Option<Self> {
+        match (v.x, v.y) {
+            (x, y) if x == 0.0 && y == 0.0 => None,
+            (x, y) if x.abs() >= y.abs() && x < 0.0 => Some(Direction::Left),
+            (x, y) if x.abs() >= y.abs() => Some(Direction::Right),
+            (_, y) if y < 0.0 => Some(Direction::Up),
+            _ => Some(Direction::Down),
+        }
+    }
+
+    /// The direction after turning left
+    pub fn left(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    /// The direction after turning right
+    pub fn right(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn is_perpendicular(&self, other: &Self) -> bool {
+        *other == self.left() || *self == other.left()
+    }
+
     pub fn rotation(&self, base_angle: f64) -> f64 {
         -base_angle
             + match self {
@@ -57,11 +97,16 @@ impl Direction {
     }
 }
 
+/// Units per second
+pub const SNAKE_SPEED: f32 = 100.0;
+
 #[hyperfold_engine::component(Singleton)]
 struct Snake {
+    /// Segments including the head, which is always segment 0
     pub body_count: usize,
-    pub pivot_offset: usize,
-    pub pivots: VecDeque<(PointF, Direction)>,
+    pub trail: Trail,
+    /// Movement is stopped, effects and animations keep playing
+    pub paused: bool,
 }
 
 #[hyperfold_engine::system]
@@ -71,49 +116,74 @@ fn new_snake(
     events: &mut dyn Events,
     r: &Renderer,
     am: &mut AssetManager,
-    camera: &mut Camera,
 ) {
-    camera.0.set_pos(0.0, 0.0, Align::Center, Align::Center);
+    // A saved run carries on where it was left, `resume_game` restores the rest of it
+    let save = load_game();
+    let (snake, speed) = match &save {
+        Some(save) => (save.snake(), save.speed),
+        None => (
+            Snake {
+                body_count: 1,
+                trail: Trail::new(PointF::new()),
+                paused: false,
+            },
+            SNAKE_SPEED,
+        ),
+    };
+    let head = snake.trail.head();
+    let heading = snake.trail.heading();
 
     let e = Entity::new();
-    let anim = Animation::new(8, 150);
+    let anim = snake_asset(SnakeAsset::Snake).animation();
+    let sprite = SpriteAsset::animated(SnakeAsset::Snake, anim);
+    let mut asset = sprite.render_asset(r, am);
+    if let Some(direction) = heading {
+        asset = asset.with_rotation(direction.rotation(90.0), None);
+    }
     add_components!(
         entities,
         e,
         Playing::Label,
-        Snake {
-            body_count: 0,
-            pivot_offset: 0,
-            pivots: VecDeque::new()
-        },
+        snake,
         Elevation(Elevations::Snake as u8),
-        RenderComponent::new(
-            RenderAsset::from_file("res/snake/snake_ss.png", r, am).with_animation(anim)
-        ),
-        HitBox(Rect::from_center(0.0, 0.0, SNAKE_HB_W, SNAKE_HB_W)),
-        Position(Rect::from_center(0.0, 0.0, SNAKE_W, SNAKE_W)),
-        PhysicsData {
-            v: PointF::new(),
-            a: PointF::new(),
-            boundary: Some(Rect::from(0.0, 0.0, W_F, W_F, Align::Center, Align::Center))
-        },
-        Speed(100.0),
+        RenderComponent::new(asset),
+        sprite,
+        HitBox(Rect::from_center(head.x, head.y, SNAKE_HB_W, SNAKE_HB_W)),
+        Position(Rect::from_center(head.x, head.y, SNAKE_W, SNAKE_W)),
+        Motion::new(head),
+        Speed(speed),
         anim
     );
+    // Already moving
+    if let Some(direction) = heading {
+        entities.add_component(
+            e,
+            SnakeBody {
+                direction,
+                snake_idx: 0,
+            },
+        );
+    }
 
     // Snake body animator
+    let body_sprite = snake_asset(SnakeAsset::SnakeBody);
     let e = Entity::new();
     add_components!(
         entities,
         e,
         Playing::Label,
         SnakeBodyAnim {
-            timer: Timer::new(150),
+            timer: Timer::new(body_sprite.frame_ms),
             frame: 0,
+            w: body_sprite.w,
+            h: body_sprite.h,
         }
     );
 
-    events.new_event(SpawnFruit);
+    match save {
+        Some(save) => events.new_event(ResumeGame(save)),
+        None => events.new_event(SpawnFruit),
+    }
 }
 
 #[hyperfold_engine::component]
@@ -126,64 +196,99 @@ components!(
     hit_box: &'a HitBox,
 );
 
-components!(SnakePivots, pivots: &'a Snake, speed: &'a Speed);
-components!(SnakePivotsMut, pivots: &'a mut Snake, speed: &'a Speed);
+components!(SnakeTrail, snake: &'a Snake, speed: &'a Speed);
+components!(SnakeTrailMut, snake: &'a mut Snake, speed: &'a Speed);
 
 components!(
-    SnakePhysics,
-    pos: &'a Position,
-    physics: &'a mut PhysicsData,
-    speed: &'a Speed,
-    tex: &'a mut RenderComponent,
+    SnakeHead,
     snake: &'a mut Snake,
     body: Option<&'a SnakeBody>,
 );
 
+/// The head turned, fired as the turn is made but not for presses that keep its heading
+#[hyperfold_engine::event]
+struct SnakeTurn(pub Direction);
+
 #[hyperfold_engine::system]
-fn move_snake(key: &Key, snake: SnakePhysics, entities: &mut dyn Components) {
-    if key.0.pressed() {
-        let direction = match key.0.key {
-            SDL_KeyCode::SDLK_a => Direction::Left,
-            SDL_KeyCode::SDLK_d => Direction::Right,
-            SDL_KeyCode::SDLK_w => Direction::Up,
-            SDL_KeyCode::SDLK_s => Direction::Down,
-            _ => return,
-        };
+fn move_snake(
+    input: &SnakeInput,
+    snake: SnakeHead,
+    entities: &mut dyn Components,
+    events: &mut dyn Events,
+) {
+    // A still snake faces up
+    let heading = snake.snake.trail.heading().unwrap_or(Direction::Up);
+    let direction = match input.0 {
+        SnakeAction::TurnLeft => Direction::Left,
+        SnakeAction::TurnRight => Direction::Right,
+        SnakeAction::TurnUp => Direction::Up,
+        SnakeAction::TurnDown => Direction::Down,
+        SnakeAction::RotateLeft => heading.left(),
+        SnakeAction::RotateRight => heading.right(),
+        _ => return,
+    };
+    if snake.snake.paused {
+        return;
+    }
 
-        // First pivot
-        if snake.body.is_none() {
-            entities.add_component(
-                *snake.eid,
-                SnakeBody {
-                    direction,
-                    snake_idx: snake.snake.body_count,
-                    pivot_idx: (snake.snake.pivot_offset + snake.snake.pivots.len()).max(1) - 1,
-                },
-            );
-            snake.snake.body_count += 1;
-        }
+    // First turn, the head starts moving on the next tick and rotates to face its heading
+    if snake.body.is_none() {
+        entities.add_component(
+            *snake.eid,
+            SnakeBody {
+                direction: Direction::Up,
+                snake_idx: 0,
+            },
+        );
+    }
 
-        snake
-            .snake
-            .pivots
-            .push_back((snake.pos.0.center(), direction));
+    if snake.snake.trail.turn(direction) {
+        events.new_event(SnakeTurn(direction));
+        events.new_event(PlaySound(SnakeSound::Turn));
     }
 }
 
+/// Ticks skip the snake while paused, pausing saves the run
 #[hyperfold_engine::system]
-fn collide_snake(_: &Update, snake: SnakePos, bodies: Vec<SnakeBodyPos>, events: &mut dyn Events) {
-    if bodies
-        .iter()
-        .find(|body| body.eid != snake.eid && body.hit_box.0.intersects(&snake.hit_box.0))
-        .is_some()
-    {
-        events.set_state(GameOver::Data);
+fn toggle_pause(input: &SnakeInput, snake: SnakeTrailMut, events: &mut dyn Events) {
+    if input.0 == SnakeAction::Pause {
+        snake.snake.paused = !snake.snake.paused;
+        if snake.snake.paused {
+            events.new_event(SaveGame);
+        }
     }
 }
 
+/// The head left the board
+#[hyperfold_engine::event]
+struct HitWall;
+
+/// Checks the head against every snake's segments and the walls
 #[hyperfold_engine::system]
-fn collide_wall(collide: &BoundaryCollision, snake: SnakePos, events: &mut dyn Events) {
-    if collide.0 == *snake.eid {
+fn collide_snake(
+    _: &Tick,
+    snake: SnakePos,
+    CollisionsCS { collisions, .. }: CollisionsCS,
+    events: &mut dyn Events,
+) {
+    let (mut hit_body, mut hit_wall) = (false, false);
+    for hit in collisions.0.query(&snake.hit_box.0) {
+        match hit {
+            Collider::Segment(e) => hit_body |= e != *snake.eid,
+            Collider::Wall(_) => hit_wall = true,
+            Collider::Fruit(_) => (),
+        }
+    }
+    if hit_wall {
+        events.new_event(HitWall);
+    } else if hit_body {
+        events.new_event(Died(DeathCause::Body));
         events.set_state(GameOver::Data);
     }
 }
+
+#[hyperfold_engine::system]
+fn collide_wall(_: &HitWall, events: &mut dyn Events) {
+    events.new_event(Died(DeathCause::Wall));
+    events.set_state(GameOver::Data);
+}
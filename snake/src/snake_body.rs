@@ -3,10 +3,10 @@ use hyperfold_engine::{
     add_components, components,
     ecs::{entities::NewEntity, events::core::Update},
     framework::{
-        physics::{HitBox, PhysicsData, Position},
+        physics::{HitBox, Position},
         render_system::{
-            render_data::{RenderAsset, RenderDataBuilderTrait, RenderDataTrait},
-            AssetManager, Elevation, RenderComponent, Renderer,
+            render_data::{RenderAsset, RenderDataTrait},
+            RenderComponent,
         },
     },
     utils::{
@@ -18,9 +18,11 @@ use hyperfold_engine::{
 
 use crate::{
     _engine::Components,
-    elevations::Elevations,
+    collisions::{Collider, Collisions, CollisionsCS},
+    fixed_step::{Motion, Tick},
     fruit::EatFruit,
-    snake::{Direction, Snake, SnakePivotsMut},
+    snake::{Direction, SnakeTrailMut},
+    trail::Trail,
     Playing,
 };
 
@@ -30,71 +32,56 @@ pub const SNAKE_HB_W: f32 = 20.0;
 #[hyperfold_engine::component]
 struct SnakeBody {
     pub direction: Direction,
+    /// Segments behind the head, the segment sits this many `SNAKE_W` back along the trail
     pub snake_idx: usize,
-    pub pivot_idx: usize,
 }
 
+/// Scrolls the body texture one pixel per tick, see `body_renderer`
 #[hyperfold_engine::component(Singleton)]
 struct SnakeBodyAnim {
     pub timer: Timer,
     pub frame: u32,
+    pub w: u32,
+    pub h: u32,
 }
 
 #[hyperfold_engine::system]
 fn new_snake_body(
     _: &EatFruit,
-    bodies: Vec<SnakeBodies>,
-    snake: SnakePivotsMut,
+    snake: SnakeTrailMut,
+    CollisionsCS { collisions, .. }: CollisionsCS,
     entities: &mut dyn Components,
-    r: &Renderer,
-    am: &mut AssetManager,
 ) {
-    let (mut pos, direction) = match bodies
-        .iter()
-        .find(|body| body.body.snake_idx == snake.pivots.body_count - 1)
-    {
-        Some(tail) => (tail.pos.0.center(), tail.body.direction),
-        None => (PointF::new(), Direction::Up),
-    };
-
-    match direction {
-        Direction::Left => pos.x += SNAKE_W,
-        Direction::Right => pos.x -= SNAKE_W,
-        Direction::Up => pos.y += SNAKE_W,
-        Direction::Down => pos.y -= SNAKE_W,
-    }
+    // Past the end of the trail is straight on behind the tail
+    let snake_idx = snake.snake.body_count;
+    let (pos, direction) = snake.snake.trail.sample(SNAKE_W, snake_idx + 1)[snake_idx];
+    add_snake_body(pos, direction, snake_idx, collisions, entities);
+    snake.snake.body_count += 1;
+}
 
+/// Adds segment `snake_idx` without counting it, drawn by the body renderer
+pub fn add_snake_body(
+    pos: PointF,
+    direction: Direction,
+    snake_idx: usize,
+    collisions: &mut Collisions,
+    entities: &mut dyn Components,
+) {
     let e = Entity::new();
+    let hit_box = Rect::from_center(pos.x, pos.y, SNAKE_HB_W, SNAKE_HB_W);
+    collisions.0.insert(Collider::Segment(e), hit_box);
     add_components!(
         entities,
         e,
         Playing::Label,
         SnakeBody {
             direction,
-            snake_idx: snake.pivots.body_count,
-            pivot_idx: snake.pivots.pivot_offset
+            snake_idx
         },
-        Elevation(Elevations::Snake as u8),
-        RenderComponent::new(
-            RenderAsset::from_file("res/snake/snake_body.png", r, am)
-                .with_area(Some(Rect {
-                    x: 0.0,
-                    y: 0.0,
-                    w: 17.0,
-                    h: 17.0
-                }))
-                .with_rotation(direction.rotation(90.0), None)
-        ),
-        HitBox(Rect::from_center(pos.x, pos.y, SNAKE_HB_W, SNAKE_HB_W)),
+        HitBox(hit_box),
         Position(Rect::from_center(pos.x, pos.y, SNAKE_W, SNAKE_W,)),
-        PhysicsData {
-            v: direction.velocity(snake.speed.0),
-            a: PointF::new(),
-            boundary: None
-        },
+        Motion::new(pos),
     );
-
-    snake.pivots.body_count += 1;
 }
 
 components!(SnakeBodyPos, pos: &'a Position, hit_box: &'a HitBox);
@@ -103,90 +90,75 @@ components!(
     SnakeBodies,
     body: &'a mut SnakeBody,
     pos: &'a mut Position,
-    physics: &'a mut PhysicsData,
-    tex: &'a mut RenderComponent
+    motion: &'a mut Motion,
+    hit_box: &'a mut HitBox,
+    tex: Option<&'a mut RenderComponent>
 );
 
+/// Moves the head `dist` and returns where each of `count` segments sits along the trail
+pub fn step_trail(trail: &mut Trail, dist: f32, count: usize) -> Vec<(PointF, Direction)> {
+    trail.advance(dist);
+    // Nothing behind the tail is needed again
+    trail.trim(count.saturating_sub(1) as f32 * SNAKE_W);
+    trail.sample(SNAKE_W, count)
+}
+
+/// Moves the head one step and places every segment along its trail
+/// Positions are drawn interpolated, hit boxes use the stepped position
 #[hyperfold_engine::system]
-fn update_snake_bodies(_: &Update, mut bodies: Vec<SnakeBodies>, snake: SnakePivotsMut) {
-    // Sort to avoid processing tail before other segments
-    bodies.sort_unstable_by_key(|body| body.body.snake_idx);
+fn update_snake_bodies(
+    tick: &Tick,
+    mut bodies: Vec<SnakeBodies>,
+    snake: SnakeTrailMut,
+    CollisionsCS { collisions, .. }: CollisionsCS,
+) {
+    for body in bodies.iter_mut() {
+        body.motion.begin_tick();
+    }
+    if snake.snake.paused {
+        return;
+    }
+
+    let samples = step_trail(
+        &mut snake.snake.trail,
+        snake.speed.0 * tick.dt,
+        snake.snake.body_count,
+    );
 
     for SnakeBodies {
+        eid,
         body,
-        pos,
-        physics,
+        motion,
+        hit_box,
         tex,
         ..
     } in bodies
     {
-        // Invalid pivot is not any error, could mean waiting for the next pivot or no pivots
-        if let Some((piv_pos, piv_dir)) = snake
-            .pivots
-            .pivots
-            .get(body.pivot_idx - snake.pivots.pivot_offset)
-        {
-            let diff = match body.direction {
-                Direction::Left => piv_pos.x - pos.0.cx(),
-                Direction::Right => pos.0.cx() - piv_pos.x,
-                Direction::Up => piv_pos.y - pos.0.cy(),
-                Direction::Down => pos.0.cy() - piv_pos.y,
-            };
-
-            // Passed the pivot
-            if diff >= 0.0 {
-                body.pivot_idx += 1;
-                body.direction = *piv_dir;
-
-                // Keep progress passed pivot
-                pos.0
-                    .set_pos(piv_pos.x, piv_pos.y, Align::Center, Align::Center);
-                match body.direction {
-                    Direction::Left => pos.0.x -= diff,
-                    Direction::Right => pos.0.x += diff,
-                    Direction::Up => pos.0.y -= diff,
-                    Direction::Down => pos.0.y += diff,
-                };
-
-                physics.v = piv_dir.velocity(snake.speed.0);
+        let Some(&(pos, direction)) = samples.get(body.snake_idx) else {
+            continue;
+        };
+        motion.pos = pos;
+        if direction != body.direction {
+            body.direction = direction;
+            // Only the head has its own sprite
+            if let Some(tex) = tex {
                 tex.try_as_mut(|tex: &mut RenderAsset| {
-                    tex.set_rotation(piv_dir.rotation(90.0), None);
+                    tex.set_rotation(direction.rotation(90.0), None);
                 });
-
-                // If we are the tail, remove the pivot
-                if body.snake_idx == snake.pivots.body_count - 1 {
-                    snake.pivots.pivots.pop_front();
-                    snake.pivots.pivot_offset += 1;
-                }
             }
         }
+
+        hit_box
+            .0
+            .set_pos(pos.x, pos.y, Align::Center, Align::Center);
+        collisions.0.insert(Collider::Segment(*eid), hit_box.0);
     }
 }
 
-components!(
-    labels(SnakeBody && !Snake),
-    SnakeBodyImgs,
-    tex: &'a mut RenderComponent
-);
-
 components!(SnakeBodyAnimCS, anim: &'a mut SnakeBodyAnim);
 
 #[hyperfold_engine::system]
-fn animate_snake_bodies(
-    update: &Update,
-    bodies: Vec<SnakeBodyImgs>,
-    SnakeBodyAnimCS { anim, .. }: SnakeBodyAnimCS,
-) {
+fn animate_snake_bodies(update: &Update, SnakeBodyAnimCS { anim, .. }: SnakeBodyAnimCS) {
     let n = anim.timer.add_time(update.0);
-    anim.frame = (anim.frame + n) % 17;
-    let rect = Rect {
-        x: 0.0,
-        y: anim.frame as f32,
-        w: 17.0,
-        h: 17.0,
-    };
-    for body in bodies {
-        body.tex
-            .try_as_mut(|tex: &mut RenderAsset| tex.set_area(Some(rect)));
-    }
+    anim.frame = (anim.frame + n) % anim.h.max(1);
 }
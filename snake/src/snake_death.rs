@@ -5,7 +5,7 @@ use hyperfold_engine::{
     framework::{
         physics::Position,
         render_system::{
-            render_data::{Animation, RenderAsset, RenderDataBuilderTrait, RenderDataTrait},
+            render_data::{RenderAsset, RenderDataBuilderTrait, RenderDataTrait},
             AssetManager, Elevation, RenderComponent, Renderer,
         },
     },
@@ -13,7 +13,8 @@ use hyperfold_engine::{
 };
 
 use crate::{
-    _engine::Components, elevations::Elevations, snake_body::SnakeBody, GameOver, Playing,
+    _engine::Components, elevations::Elevations, hot_reload::SpriteAsset, snake_asset,
+    snake_body::SnakeBody, GameOver, Playing, SnakeAsset,
 };
 
 #[hyperfold_engine::component]
@@ -23,7 +24,7 @@ components!(
     SnakeBodies,
     pos: &'a Position,
     body: &'a SnakeBody,
-    tex: &'a RenderComponent
+    tex: Option<&'a RenderComponent>
 );
 
 #[hyperfold_engine::system]
@@ -36,20 +37,23 @@ fn snake_death(
 ) {
     for SnakeBodies { pos, body, tex, .. } in bodies {
         let e = Entity::new();
-        let anim = Animation::once(8, 100);
-        let mut asset = RenderAsset::from_file(
-            match body.snake_idx {
-                0 => "res/snake/snake_death.png",
-                _ => "res/snake/snake_body_death.png",
-            },
-            r,
-            am,
-        )
-        .with_animation(anim);
-        tex.try_as(|ra: &RenderAsset| {
-            asset.set_render_options(ra.get_render_opts());
-            asset.set_dest(ra.get_dest_opts());
-        });
+        let key = match body.snake_idx {
+            0 => SnakeAsset::SnakeDeath,
+            _ => SnakeAsset::SnakeBodyDeath,
+        };
+        let anim = snake_asset(key).animation_once();
+        let sprite = SpriteAsset::animated(key, anim);
+        let mut asset = sprite.render_asset(r, am);
+        match tex {
+            Some(tex) => {
+                tex.try_as(|ra: &RenderAsset| {
+                    asset.set_render_options(ra.get_render_opts());
+                    asset.set_dest(ra.get_dest_opts());
+                });
+            }
+            // Body segments are drawn by the body renderer
+            None => asset.set_rotation(body.direction.rotation(90.0), None),
+        }
         add_components!(
             entities,
             e,
@@ -58,6 +62,7 @@ fn snake_death(
             Elevation(Elevations::Snake as u8),
             *pos,
             RenderComponent::new(asset),
+            sprite,
             anim
         );
     }
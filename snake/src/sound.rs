@@ -0,0 +1,132 @@
+use hyperfold_engine::{_engine::Entity, add_components, components, ecs::entities::NewEntity};
+
+use audio::{AudioSettings, Mixer, Music, Sound};
+
+use crate::{
+    _engine::Components,
+    controls::{SnakeAction, SnakeInput},
+    fruit::EatFruit,
+    fruit_effect::PowerUp,
+    stats::Died,
+    GameOver, Playing,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum SnakeSound {
+    Turn,
+    Eat,
+    PowerUp,
+    Death,
+}
+
+impl SnakeSound {
+    pub const ALL: [SnakeSound; 4] = [
+        SnakeSound::Turn,
+        SnakeSound::Eat,
+        SnakeSound::PowerUp,
+        SnakeSound::Death,
+    ];
+
+    pub fn path(&self) -> &'static str {
+        match self {
+            SnakeSound::Turn => "res/snake/sounds/turn.wav",
+            SnakeSound::Eat => "res/snake/sounds/eat.wav",
+            SnakeSound::PowerUp => "res/snake/sounds/power_up.wav",
+            SnakeSound::Death => "res/snake/sounds/death.wav",
+        }
+    }
+}
+
+pub const PLAYING_MUSIC: &str = "res/snake/music/playing.wav";
+pub const GAME_OVER_MUSIC: &str = "res/snake/music/game_over.wav";
+
+#[hyperfold_engine::event]
+struct PlaySound(pub SnakeSound);
+
+/// Fields drop in order, so sounds and music are freed before the mixer closes
+#[hyperfold_engine::component(Singleton)]
+struct Audio {
+    /// Indexed by `SnakeSound`, None if the sound couldn't be loaded
+    pub sounds: Vec<Option<Sound>>,
+    pub playing_music: Option<Music>,
+    pub game_over_music: Option<Music>,
+    pub mixer: Mixer,
+}
+
+impl Audio {
+    pub fn play(&self, sound: SnakeSound) {
+        if let Some(sound) = &self.sounds[sound as usize] {
+            self.mixer.play(sound);
+        }
+    }
+
+    pub fn play_music(&self, music: &Option<Music>) {
+        match music {
+            Some(music) => self.mixer.play_music(music),
+            None => self.mixer.stop_music(),
+        }
+    }
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_audio(entities: &mut dyn Components) {
+    let mixer = Mixer::new(AudioSettings::load());
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        Audio {
+            sounds: SnakeSound::ALL
+                .iter()
+                .map(|sound| mixer.load_sound(sound.path()))
+                .collect(),
+            playing_music: mixer.load_music(PLAYING_MUSIC),
+            game_over_music: mixer.load_music(GAME_OVER_MUSIC),
+            mixer
+        }
+    );
+}
+
+components!(AudioCS, audio: &'a mut Audio);
+
+#[hyperfold_engine::system]
+fn play_sound(sound: &PlaySound, AudioCS { audio, .. }: AudioCS) {
+    audio.play(sound.0);
+}
+
+#[hyperfold_engine::system]
+fn eat_sound(_: &EatFruit, AudioCS { audio, .. }: AudioCS) {
+    audio.play(SnakeSound::Eat);
+}
+
+#[hyperfold_engine::system]
+fn power_up_sound(_: &PowerUp, AudioCS { audio, .. }: AudioCS) {
+    audio.play(SnakeSound::PowerUp);
+}
+
+#[hyperfold_engine::system]
+fn death_sound(_: &Died, AudioCS { audio, .. }: AudioCS) {
+    audio.play(SnakeSound::Death);
+}
+
+#[hyperfold_engine::system]
+fn playing_music(_: &Playing::OnEnter, AudioCS { audio, .. }: AudioCS) {
+    audio.play_music(&audio.playing_music);
+}
+
+#[hyperfold_engine::system]
+fn game_over_music(_: &GameOver::OnEnter, AudioCS { audio, .. }: AudioCS) {
+    audio.play_music(&audio.game_over_music);
+}
+
+#[hyperfold_engine::system]
+fn audio_keys(input: &SnakeInput, AudioCS { audio, .. }: AudioCS) {
+    let mut settings = audio.mixer.settings();
+    match input.0 {
+        SnakeAction::Mute => settings.muted = !settings.muted,
+        SnakeAction::VolumeDown => settings.volume_down(),
+        SnakeAction::VolumeUp => settings.volume_up(),
+        _ => return,
+    }
+    audio.mixer.set_settings(settings);
+}
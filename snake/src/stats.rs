@@ -0,0 +1,428 @@
+use std::{collections::BTreeMap, fs};
+
+use hyperfold_engine::{
+    _engine::Entity,
+    add_components, components,
+    ecs::entities::NewEntity,
+    framework::{
+        event_system::events::Key,
+        physics::Position,
+        render_system::{
+            render_data::RenderTexture, AssetManager, Elevation, RenderComponent, Renderer, Texture,
+        },
+    },
+    sdl2::SDL_KeyCode,
+    utils::{colors::WHITE, rect::Rect},
+};
+use serde::{Deserialize, Serialize};
+
+use assets::{
+    settings::write_file,
+    strings::{tr, tr_with},
+};
+use input::Args;
+
+use crate::{
+    _engine::{Components, Events},
+    controls::{Lines, SnakeAction, SnakeInput, LINE_H},
+    elevations::Elevations,
+    fixed_step::{FixedStepCS, Tick},
+    fruit::EatFruit,
+    ghost::GhostRaceCS,
+    save_game::SaveGame,
+    scaled_text,
+    snake::SnakeTrail,
+    theme::theme,
+    GameOver, GameOverEids, Playing, W_F, W_I,
+};
+
+/// Totals across every game played, one `<profile>.json` per profile
+pub const STATS_DIR: &str = "save/snake_stats";
+/// Set with `--profile <name>`
+pub const DEFAULT_PROFILE: &str = "default";
+/// Bump when the format changes, stats from other versions are set aside rather than lost
+pub const STATS_VERSION: u32 = 1;
+
+/// What ended a game
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum DeathCause {
+    Wall,
+    /// The snake's own body
+    Body,
+    /// Boards don't have obstacles yet, so this is always zero for now
+    Obstacle,
+}
+
+impl DeathCause {
+    pub const ALL: &'static [Self] = &[DeathCause::Wall, DeathCause::Body, DeathCause::Obstacle];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            DeathCause::Wall => "wall",
+            DeathCause::Body => "self",
+            DeathCause::Obstacle => "obstacle",
+        }
+    }
+}
+
+/// The snake died, fired before the game is over
+#[hyperfold_engine::event]
+struct Died(pub DeathCause);
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    Classic,
+    /// Racing the best run's ghost
+    Ghost,
+}
+
+impl GameMode {
+    pub const ALL: &'static [Self] = &[GameMode::Classic, GameMode::Ghost];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::Ghost => "ghost",
+        }
+    }
+}
+
+/// Falls back to `DEFAULT_PROFILE` without a name or with one that isn't a plain file name
+pub fn profile(name: Option<String>) -> String {
+    match name {
+        Some(name)
+            if !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+        {
+            name
+        }
+        Some(name) => {
+            eprintln!(
+                "Ignoring --profile '{name}': use letters, digits, '_' and '-', using {DEFAULT_PROFILE}"
+            );
+            DEFAULT_PROFILE.to_string()
+        }
+        None => DEFAULT_PROFILE.to_string(),
+    }
+}
+
+pub fn stats_file(profile: &str) -> String {
+    format!("{STATS_DIR}/{profile}.json")
+}
+
+/// Written next to the game so it is easy to find and share
+pub fn stats_export_file(profile: &str) -> String {
+    format!("snake_stats_{profile}.json")
+}
+
+fn write_json(file: &str, text: serde_json::Result<String>) -> Result<(), String> {
+    text.map_err(|e| e.to_string())
+        .and_then(|text| write_file(file, text).map_err(|e| e.to_string()))
+}
+
+/// Missing fields start at zero so older files still load
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub version: u32,
+    pub games: u32,
+    pub fruits: u32,
+    /// Segments including the head
+    pub longest_snake: usize,
+    /// Seconds the snake moved in the longest game
+    pub longest_survival_s: f32,
+    /// Keyed by `DeathCause::id`
+    pub deaths: BTreeMap<String, u32>,
+    /// Seconds the snake moved, keyed by `GameMode::id`
+    pub time_played_s: BTreeMap<String, f32>,
+}
+
+impl LifetimeStats {
+    /// Starts from zero without a file, unreadable files are moved aside so they aren't overwritten
+    fn load(profile: &str) -> Self {
+        let fresh = Self {
+            version: STATS_VERSION,
+            ..Self::default()
+        };
+        let file = stats_file(profile);
+        let Ok(text) = fs::read_to_string(&file) else {
+            return fresh;
+        };
+        let err = match serde_json::from_str::<Self>(&text) {
+            Ok(stats) if stats.version == STATS_VERSION => return stats,
+            Ok(stats) => format!(
+                "version {} is not supported, expected {STATS_VERSION}",
+                stats.version
+            ),
+            Err(e) => e.to_string(),
+        };
+        let backup = format!("{file}.old");
+        eprintln!("Could not read stats {file}: {err}, moving it to {backup}");
+        if let Err(e) = fs::rename(&file, &backup) {
+            eprintln!("Could not move stats to {backup}: {e}");
+        }
+        fresh
+    }
+
+    fn save(&self, profile: &str) {
+        let file = stats_file(profile);
+        if let Err(e) = write_json(&file, serde_json::to_string(self)) {
+            eprintln!("Could not save stats to {file}: {e}");
+        }
+    }
+
+    /// Readable JSON for players to keep or share
+    fn export(&self, file: &str) -> Result<(), String> {
+        write_json(file, serde_json::to_string_pretty(self))
+    }
+}
+
+/// `h:mm:ss`, or `m:ss` under an hour
+fn duration(s: f32) -> String {
+    let s = s.max(0.0) as u64;
+    match (s / 3600, s / 60 % 60, s % 60) {
+        (0, m, s) => format!("{m}:{s:02}"),
+        (h, m, s) => format!("{h}:{m:02}:{s:02}"),
+    }
+}
+
+#[hyperfold_engine::component(Singleton)]
+struct Stats {
+    /// Whose lifetime stats these are
+    pub profile: String,
+    pub lifetime: LifetimeStats,
+    /// The mode of the game being played
+    pub mode: GameMode,
+    /// Seconds the snake has moved this game
+    pub survived_s: f32,
+    /// Shown on the stats screen
+    pub status: String,
+}
+
+#[hyperfold_engine::system(Init)]
+fn new_stats(entities: &mut dyn Components) {
+    let e = Entity::new();
+    let profile = profile(Args::get().value("profile"));
+    add_components!(
+        entities,
+        e,
+        Stats {
+            lifetime: LifetimeStats::load(&profile),
+            profile,
+            mode: GameMode::Classic,
+            survived_s: 0.0,
+            status: String::new()
+        }
+    );
+}
+
+components!(StatsCS, stats: &'a mut Stats);
+
+/// A resumed game is reset again by `resume_game`
+#[hyperfold_engine::system]
+fn start_stats(
+    _: &Playing::OnEnter,
+    StatsCS { stats, .. }: StatsCS,
+    GhostRaceCS { ghost, .. }: GhostRaceCS,
+    FixedStepCS { step, .. }: FixedStepCS,
+) {
+    stats.mode = match ghost.raced(step.hz).is_some() {
+        true => GameMode::Ghost,
+        false => GameMode::Classic,
+    };
+    stats.survived_s = 0.0;
+}
+
+/// Only counts time the snake moves, so pauses aren't played time
+#[hyperfold_engine::system]
+fn track_time(tick: &Tick, snake: SnakeTrail, StatsCS { stats, .. }: StatsCS) {
+    if snake.snake.paused {
+        return;
+    }
+    stats.survived_s += tick.dt;
+    *stats
+        .lifetime
+        .time_played_s
+        .entry(stats.mode.id().to_string())
+        .or_default() += tick.dt;
+}
+
+#[hyperfold_engine::system]
+fn count_fruit(_: &EatFruit, StatsCS { stats, .. }: StatsCS) {
+    stats.lifetime.fruits += 1;
+}
+
+/// Stats are saved with the run so resuming it doesn't count anything twice
+#[hyperfold_engine::system]
+fn save_stats(_: &SaveGame, StatsCS { stats, .. }: StatsCS) {
+    stats.lifetime.save(&stats.profile);
+}
+
+#[hyperfold_engine::system]
+fn record_death(died: &Died, snake: SnakeTrail, StatsCS { stats, .. }: StatsCS) {
+    let lifetime = &mut stats.lifetime;
+    lifetime.games += 1;
+    *lifetime.deaths.entry(died.0.id().to_string()).or_default() += 1;
+    lifetime.longest_snake = lifetime.longest_snake.max(snake.snake.body_count);
+    lifetime.longest_survival_s = lifetime.longest_survival_s.max(stats.survived_s);
+    lifetime.save(&stats.profile);
+}
+
+#[hyperfold_engine::state]
+struct StatsMenu;
+
+#[hyperfold_engine::component(Singleton)]
+struct StatsScreen;
+
+components!(labels(StatsScreen), StatsScreenEids);
+
+components!(
+    labels(StatsScreen),
+    StatsScreenTex,
+    tex: &'a mut RenderComponent
+);
+
+pub fn stats_texture(stats: &Stats, r: &Renderer, am: &mut AssetManager) -> Texture {
+    let theme = theme();
+    let tex = Texture::new(r, W_I, W_I, theme.menu_overlay());
+    let lines = Lines {
+        tex: &tex,
+        font: theme.font(),
+        line_h: scaled_text(LINE_H),
+    };
+    let lifetime = &stats.lifetime;
+    let value = |key: &str, value: String| tr_with(key, &[("value", &value)]);
+
+    let mut text = vec![
+        value("stats_profile", stats.profile.clone()),
+        value("stats_games", lifetime.games.to_string()),
+        value("stats_fruits", lifetime.fruits.to_string()),
+        value("stats_longest_snake", lifetime.longest_snake.to_string()),
+        value(
+            "stats_longest_survival",
+            duration(lifetime.longest_survival_s),
+        ),
+    ];
+    text.extend(DeathCause::ALL.iter().map(|cause| {
+        let count = lifetime.deaths.get(cause.id()).copied().unwrap_or(0);
+        tr_with(
+            "stats_deaths",
+            &[
+                ("cause", &tr(&format!("death.{}", cause.id()))),
+                ("value", &count.to_string()),
+            ],
+        )
+    }));
+    text.extend(GameMode::ALL.iter().map(|mode| {
+        let s = lifetime
+            .time_played_s
+            .get(mode.id())
+            .copied()
+            .unwrap_or(0.0);
+        tr_with(
+            "stats_time",
+            &[
+                ("mode", &tr(&format!("mode.{}", mode.id()))),
+                ("value", &duration(s)),
+            ],
+        )
+    }));
+
+    // Title with a gap after it, then the stats, status and help
+    lines.draw(&tr("stats_title"), 0, WHITE, r, am);
+    for (i, text) in text.iter().enumerate() {
+        lines.draw(text, i + 2, WHITE, r, am);
+    }
+    let status_line = text.len() + 3;
+    lines.draw(&stats.status, status_line, WHITE, r, am);
+    lines.draw(&tr("stats_help"), status_line + 1, WHITE, r, am);
+    tex
+}
+
+/// The stats action opens the screen from game over and closes it again
+#[hyperfold_engine::system]
+fn toggle_stats(
+    input: &SnakeInput,
+    game_over: Vec<GameOverEids>,
+    screens: Vec<StatsScreenEids>,
+    events: &mut dyn Events,
+) {
+    if input.0 != SnakeAction::Stats {
+        return;
+    }
+    if !game_over.is_empty() {
+        events.set_state(StatsMenu::Data);
+    } else if !screens.is_empty() {
+        events.set_state(GameOver::Data);
+    }
+}
+
+#[hyperfold_engine::system]
+fn stats_screen(
+    _: &StatsMenu::OnEnter,
+    StatsCS { stats, .. }: StatsCS,
+    entities: &mut dyn Components,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    stats.status = String::new();
+    let tex = stats_texture(stats, r, am);
+    let e = Entity::new();
+    add_components!(
+        entities,
+        e,
+        StatsScreen,
+        StatsMenu::Label,
+        Elevation(Elevations::StatsScreen as u8),
+        RenderComponent::new(RenderTexture::new(Some(tex))),
+        Position(Rect::from_center(0.0, 0.0, W_F, W_F))
+    );
+}
+
+/// Fixed keys like the controls screen, so bindings can't lock players in
+#[hyperfold_engine::system]
+fn stats_keys(
+    key: &Key,
+    StatsCS { stats, .. }: StatsCS,
+    screens: Vec<StatsScreenTex>,
+    events: &mut dyn Events,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    if !key.0.pressed() || screens.is_empty() {
+        return;
+    }
+    match key.0.key {
+        SDL_KeyCode::SDLK_x => {
+            let file = stats_export_file(&stats.profile);
+            stats.status = match stats.lifetime.export(&file) {
+                Ok(()) => tr_with("stats_exported", &[("file", &file)]),
+                Err(e) => {
+                    eprintln!("Could not export stats to {file}: {e}");
+                    tr_with("stats_export_failed", &[("file", &file)])
+                }
+            };
+            for StatsScreenTex { tex, .. } in screens {
+                *tex = RenderComponent::new(RenderTexture::new(Some(stats_texture(stats, r, am))));
+            }
+        }
+        SDL_KeyCode::SDLK_ESCAPE => events.set_state(GameOver::Data),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_defaults_and_validates() {
+        assert_eq!(profile(None), DEFAULT_PROFILE);
+        assert_eq!(profile(Some("sam_2-b".to_string())), "sam_2-b");
+        assert_eq!(profile(Some(String::new())), DEFAULT_PROFILE);
+        assert_eq!(profile(Some("../other".to_string())), DEFAULT_PROFILE);
+        assert_eq!(stats_file("sam"), format!("{STATS_DIR}/sam.json"));
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    fs,
+    ops::Deref,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use hyperfold_engine::{framework::render_system::font::TIMES, sdl2::SDL_Color};
+use serde::Deserialize;
+
+use assets::{
+    accessibility::accessibility, hot_reload::versioned, settings::save_file, AssetEntry, Manifest,
+};
+use grid::GridStyle;
+
+use crate::SnakeAsset;
+
+/// One `<theme>.json` per theme
+pub const THEME_DIR: &str = "res/snake/themes";
+pub const DEFAULT_THEME: &str = "classic";
+pub const THEME_FILE: &str = "save/snake_theme.txt";
+
+/// Replaces every theme's board with high contrast on
+pub const HIGH_CONTRAST_GRID: GridStyle = GridStyle {
+    background: SDL_Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    },
+    line: SDL_Color {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    },
+    line_w: -3.0,
+};
+/// Overlays are at least this opaque with high contrast on
+pub const HIGH_CONTRAST_ALPHA: u8 = 208;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BoardData {
+    background: [u8; 4],
+    line: [u8; 4],
+    /// Negative widths draw inside each cell
+    line_w: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeData {
+    name: String,
+    board: BoardData,
+    /// Asset manifest with the snake and fruit sprites
+    assets: String,
+    font: Option<String>,
+    /// Behind the game over text
+    overlay: [u8; 4],
+    /// Behind the controls screen
+    menu_overlay: [u8; 4],
+}
+
+fn color([r, g, b, a]: [u8; 4]) -> SDL_Color {
+    SDL_Color { r, g, b, a }
+}
+
+pub struct Theme {
+    pub id: String,
+    pub name: String,
+    grid: GridStyle,
+    /// Loaded with the theme, so switching or reloading it picks up its sprites
+    pub assets: Manifest<SnakeAsset>,
+    font: String,
+    overlay: SDL_Color,
+    menu_overlay: SDL_Color,
+}
+
+impl Theme {
+    pub fn path(id: &str) -> String {
+        format!("{THEME_DIR}/{id}.json")
+    }
+
+    /// Fails if the theme or its asset manifest is missing or invalid
+    pub fn load(id: &str) -> Result<Self, String> {
+        let path = Self::path(id);
+        let data: ThemeData = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Could not load theme {path}: {e}"))?;
+        Ok(Self {
+            id: id.to_string(),
+            name: data.name,
+            grid: GridStyle {
+                background: color(data.board.background),
+                line: color(data.board.line),
+                line_w: data.board.line_w,
+            },
+            assets: Manifest::load(&data.assets).map_err(|e| e.to_string())?,
+            font: data.font.unwrap_or(TIMES.to_string()),
+            overlay: color(data.overlay),
+            menu_overlay: color(data.menu_overlay),
+        })
+    }
+
+    /// Font file to load, changes when the font is hot reloaded
+    pub fn font(&self) -> String {
+        versioned(&self.font)
+    }
+
+    /// The board style, overridden by high contrast
+    pub fn grid(&self) -> GridStyle {
+        match accessibility().high_contrast {
+            true => HIGH_CONTRAST_GRID,
+            false => self.grid,
+        }
+    }
+
+    /// Darkened with high contrast so text stands out
+    fn contrast(color: SDL_Color) -> SDL_Color {
+        match accessibility().high_contrast {
+            true => SDL_Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: color.a.max(HIGH_CONTRAST_ALPHA),
+            },
+            false => color,
+        }
+    }
+
+    /// Behind the game over text
+    pub fn overlay(&self) -> SDL_Color {
+        Self::contrast(self.overlay)
+    }
+
+    /// Behind the controls screen
+    pub fn menu_overlay(&self) -> SDL_Color {
+        Self::contrast(self.menu_overlay)
+    }
+}
+
+fn current() -> &'static RwLock<Arc<Theme>> {
+    static CURRENT: OnceLock<RwLock<Arc<Theme>>> = OnceLock::new();
+    CURRENT.get_or_init(|| {
+        let saved = fs::read_to_string(THEME_FILE)
+            .ok()
+            .and_then(|id| Theme::load(id.trim()).map_err(|e| eprintln!("{e}")).ok());
+        let theme =
+            saved.unwrap_or_else(|| Theme::load(DEFAULT_THEME).unwrap_or_else(|e| panic!("{e}")));
+        RwLock::new(Arc::new(theme))
+    })
+}
+
+/// The selected theme, loaded on first use
+pub fn theme() -> Arc<Theme> {
+    current().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// An entry in a theme's manifest, keeping that theme alive while it is used
+pub struct ThemeAsset {
+    theme: Arc<Theme>,
+    key: SnakeAsset,
+}
+
+impl ThemeAsset {
+    pub fn new(theme: Arc<Theme>, key: SnakeAsset) -> Self {
+        Self { theme, key }
+    }
+}
+
+impl Deref for ThemeAsset {
+    type Target = AssetEntry;
+
+    fn deref(&self) -> &Self::Target {
+        self.theme.assets.get(self.key)
+    }
+}
+
+/// Every theme in `THEME_DIR`, sorted
+pub fn themes() -> Vec<String> {
+    let mut themes = fs::read_dir(THEME_DIR)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    themes.sort();
+    themes
+}
+
+/// Switches and saves the theme, the board is rebuilt when the next game starts
+/// Sprites drawn from then on use the new theme's assets
+pub fn set_theme(id: &str) -> Result<(), String> {
+    let theme = Theme::load(id)?;
+    *current().write().unwrap_or_else(|e| e.into_inner()) = Arc::new(theme);
+    save_file(THEME_FILE, "theme", id);
+    Ok(())
+}
+
+/// Rereads the current theme, e.g. after its file changed
+/// Keeps the old theme if the new file is invalid
+pub fn reload() {
+    let id = theme().id.clone();
+    match Theme::load(&id) {
+        Ok(theme) => *current().write().unwrap_or_else(|e| e.into_inner()) = Arc::new(theme),
+        Err(e) => eprintln!("{e}"),
+    }
+}
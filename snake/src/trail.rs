@@ -0,0 +1,382 @@
+use std::collections::VecDeque;
+
+use hyperfold_engine::utils::rect::PointF;
+
+use crate::snake::Direction;
+
+/// The path the head has taken, each segment sits a fixed distance along it
+/// Only the head moves itself, the body is sampled from the trail every tick, so a segment
+/// moved past several corners in one tick lands exactly where the rest of the step takes it
+pub struct Trail {
+    /// The head followed by every turn still under the body, newest first
+    /// The last point is where the trail ends, past it the trail runs on straight
+    points: VecDeque<PointF>,
+    /// None until the first turn
+    heading: Option<Direction>,
+}
+
+/// Length of a straight line between `a` and `b`
+fn length(a: PointF, b: PointF) -> f32 {
+    (a.x - b.x).abs() + (a.y - b.y).abs()
+}
+
+/// Direction of travel from `from` to `to`, None if they are the same point
+fn travel(from: PointF, to: PointF) -> Option<Direction> {
+    Direction::from_vector(PointF {
+        x: to.x - from.x,
+        y: to.y - from.y,
+    })
+}
+
+/// The point `dist` back from `pos` when travelling along `dir`
+fn back(pos: PointF, dir: Direction, dist: f32) -> PointF {
+    let v = dir.velocity(dist);
+    PointF {
+        x: pos.x - v.x,
+        y: pos.y - v.y,
+    }
+}
+
+impl Trail {
+    pub fn new(head: PointF) -> Self {
+        Self {
+            points: VecDeque::from([head]),
+            heading: None,
+        }
+    }
+
+    /// Rebuilds a trail from its `points`, None if there are none
+    pub fn from_points(
+        points: impl IntoIterator<Item = PointF>,
+        heading: Option<Direction>,
+    ) -> Option<Self> {
+        let points: VecDeque<_> = points.into_iter().collect();
+        (!points.is_empty()).then_some(Self { points, heading })
+    }
+
+    /// The head, every corner and the end of the trail
+    pub fn points(&self) -> impl Iterator<Item = PointF> + '_ {
+        self.points.iter().copied()
+    }
+
+    pub fn head(&self) -> PointF {
+        self.points[0]
+    }
+
+    /// Direction the head is moving in, None while the snake is still
+    pub fn heading(&self) -> Option<Direction> {
+        self.heading
+    }
+
+    /// Turns the head where it is, false if it was already heading that way
+    pub fn turn(&mut self, direction: Direction) -> bool {
+        if self.heading == Some(direction) {
+            return false;
+        }
+        // Turning again before moving reuses the corner
+        if self.points.get(1) != Some(&self.head()) {
+            self.points.push_front(self.head());
+        }
+        self.heading = Some(direction);
+        true
+    }
+
+    /// Moves the head `dist` along its heading
+    pub fn advance(&mut self, dist: f32) {
+        if let Some(dir) = self.heading {
+            self.points[0] = back(self.points[0], dir, -dist);
+        }
+    }
+
+    /// Direction of travel where the trail ends, a still snake faces up
+    fn end_direction(&self) -> Direction {
+        self.points
+            .iter()
+            .zip(self.points.iter().skip(1))
+            .rev()
+            .find_map(|(newer, older)| travel(*older, *newer))
+            .or(self.heading)
+            .unwrap_or(Direction::Up)
+    }
+
+    /// Drops the trail more than `len` behind the head
+    pub fn trim(&mut self, len: f32) {
+        let mut walked = 0.0;
+        for i in 1..self.points.len() {
+            let (newer, older) = (self.points[i - 1], self.points[i]);
+            let seg_len = length(newer, older);
+            if walked + seg_len >= len {
+                if let Some(dir) = travel(older, newer) {
+                    self.points[i] = back(newer, dir, len - walked);
+                }
+                self.points.truncate(i + 1);
+                return;
+            }
+            walked += seg_len;
+        }
+    }
+
+    /// `count` points `spacing` apart along the trail starting at the head, with the
+    /// direction of travel at each. Runs once along the trail however many are asked for
+    pub fn sample(&self, spacing: f32, count: usize) -> Vec<(PointF, Direction)> {
+        let mut samples = Vec::with_capacity(count);
+        if count == 0 {
+            return samples;
+        }
+        samples.push((self.head(), self.heading.unwrap_or(Direction::Up)));
+
+        let end_dir = self.end_direction();
+        // `walked` is the distance from the head to the newer end of segment `i`
+        let (mut i, mut walked) = (1, 0.0);
+        for n in 1..count {
+            let target = n as f32 * spacing;
+            while let Some(&older) = self.points.get(i) {
+                let newer = self.points[i - 1];
+                let seg_len = length(newer, older);
+                match travel(older, newer) {
+                    Some(dir) if walked + seg_len >= target => {
+                        samples.push((back(newer, dir, target - walked), dir));
+                        break;
+                    }
+                    _ => {
+                        walked += seg_len;
+                        i += 1;
+                    }
+                }
+            }
+            if i == self.points.len() {
+                let end = self.points[i - 1];
+                samples.push((back(end, end_dir, target - walked), end_dir));
+            }
+        }
+        samples
+    }
+
+    /// Where the head turned, newest first, up to the end of the trail
+    pub fn corners(&self) -> impl Iterator<Item = PointF> + '_ {
+        self.points
+            .iter()
+            .skip(1)
+            .take(self.points.len().saturating_sub(2))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: f32, y: f32) -> PointF {
+        PointF { x, y }
+    }
+
+    /// Samples as `(x, y, direction)` so they can be compared whole
+    fn samples(trail: &Trail, spacing: f32, count: usize) -> Vec<(f32, f32, char)> {
+        trail
+            .sample(spacing, count)
+            .into_iter()
+            .map(|(pos, dir)| {
+                let dir = match dir {
+                    Direction::Up => 'U',
+                    Direction::Down => 'D',
+                    Direction::Left => 'L',
+                    Direction::Right => 'R',
+                };
+                (pos.x, pos.y, dir)
+            })
+            .collect()
+    }
+
+    fn corners(trail: &Trail) -> Vec<(f32, f32)> {
+        trail.corners().map(|p| (p.x, p.y)).collect()
+    }
+
+    /// Right and down 10 at a time from the origin, ending on a right at (30, 20)
+    fn staircase() -> Trail {
+        let mut trail = Trail::new(p(0.0, 0.0));
+        for dir in [
+            Direction::Right,
+            Direction::Down,
+            Direction::Right,
+            Direction::Down,
+            Direction::Right,
+        ] {
+            trail.turn(dir);
+            trail.advance(10.0);
+        }
+        trail
+    }
+
+    #[test]
+    fn one_step_past_several_corners() {
+        let mut trail = staircase();
+        assert_eq!(
+            samples(&trail, 15.0, 4),
+            [
+                (30.0, 20.0, 'R'),
+                (20.0, 15.0, 'D'),
+                (10.0, 10.0, 'R'),
+                (5.0, 0.0, 'R')
+            ]
+        );
+
+        // Segment 3 is carried past all four corners by what is left of the step after each
+        trail.advance(37.0);
+        assert_eq!(
+            samples(&trail, 15.0, 7),
+            [
+                (67.0, 20.0, 'R'),
+                (52.0, 20.0, 'R'),
+                (37.0, 20.0, 'R'),
+                (22.0, 20.0, 'R'),
+                (17.0, 10.0, 'R'),
+                (10.0, 2.0, 'D'),
+                (-3.0, 0.0, 'R')
+            ]
+        );
+        assert_eq!(
+            corners(&trail),
+            [(20.0, 20.0), (20.0, 10.0), (10.0, 10.0), (10.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn several_turns_in_one_step() {
+        let mut trail = staircase();
+        // Only the last turn before moving counts, they share one corner
+        assert!(trail.turn(Direction::Up));
+        assert!(trail.turn(Direction::Left));
+        assert!(trail.turn(Direction::Down));
+        // Already heading down, nothing to record
+        assert!(!trail.turn(Direction::Down));
+        trail.advance(25.0);
+        assert!(trail.heading() == Some(Direction::Down));
+        assert_eq!(trail.corners().count(), 5);
+        assert_eq!(
+            samples(&trail, 15.0, 4),
+            [
+                (30.0, 45.0, 'D'),
+                (30.0, 30.0, 'D'),
+                (25.0, 20.0, 'R'),
+                (20.0, 10.0, 'D')
+            ]
+        );
+
+        // Turning back the way it came retraces the trail
+        trail.turn(Direction::Up);
+        trail.advance(25.0);
+        assert_eq!(
+            samples(&trail, 5.0, 2),
+            [(30.0, 20.0, 'U'), (30.0, 25.0, 'U')]
+        );
+    }
+
+    #[test]
+    fn step_size_does_not_move_segments() {
+        // The same path walked in one big step a tick and in many small ones
+        let walk = |steps: u32| {
+            let mut trail = Trail::new(p(0.0, 0.0));
+            for (i, dir) in [
+                Direction::Right,
+                Direction::Up,
+                Direction::Left,
+                Direction::Up,
+            ]
+            .into_iter()
+            .cycle()
+            .take(12)
+            .enumerate()
+            {
+                trail.turn(dir);
+                let dist = 40.0 + 8.0 * i as f32;
+                for _ in 0..steps {
+                    trail.advance(dist / steps as f32);
+                }
+            }
+            trail
+        };
+        let fast = walk(1);
+        let slow = walk(8);
+        assert_eq!(samples(&fast, 12.0, 40), samples(&slow, 12.0, 40));
+        assert_eq!(corners(&fast), corners(&slow));
+    }
+
+    #[test]
+    fn samples_keep_their_spacing_around_corners() {
+        let trail = staircase();
+        // The staircase never doubles back, so the path between samples is their distance
+        let samples = samples(&trail, 7.0, 8);
+        for pair in samples.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert_eq!((a.0 - b.0).abs() + (a.1 - b.1).abs(), 7.0);
+        }
+        assert_eq!(samples[1], (23.0, 20.0, 'R'));
+        assert_eq!(samples[2], (20.0, 16.0, 'D'));
+        assert_eq!(samples[3], (19.0, 10.0, 'R'));
+        assert_eq!(samples[4], (12.0, 10.0, 'R'));
+        assert_eq!(samples[5], (10.0, 5.0, 'D'));
+        assert_eq!(samples[6], (8.0, 0.0, 'R'));
+    }
+
+    #[test]
+    fn trim_at_a_corner() {
+        let mut trail = staircase();
+        let kept = samples(&trail, 10.0, 3);
+        trail.trim(20.0);
+        assert_eq!(corners(&trail), [(20.0, 20.0)]);
+        assert_eq!(
+            trail.points().last().map(|p| (p.x, p.y)),
+            Some((20.0, 10.0))
+        );
+        // The trail runs on straight past the corner it now ends at
+        assert_eq!(samples(&trail, 10.0, 3), kept);
+        assert_eq!(samples(&trail, 10.0, 4)[3], (20.0, 0.0, 'D'));
+
+        // Just past a corner keeps the start of the next segment
+        let mut trail = staircase();
+        trail.trim(21.0);
+        assert_eq!(corners(&trail), [(20.0, 20.0), (20.0, 10.0)]);
+        assert_eq!(
+            trail.points().last().map(|p| (p.x, p.y)),
+            Some((19.0, 10.0))
+        );
+        assert_eq!(samples(&trail, 25.0, 2)[1], (15.0, 10.0, 'R'));
+
+        // Longer than the trail changes nothing
+        let mut trail = staircase();
+        trail.trim(100.0);
+        assert_eq!(corners(&trail).len(), 4);
+        assert_eq!(samples(&trail, 10.0, 6), samples(&staircase(), 10.0, 6));
+    }
+
+    #[test]
+    fn samples_past_the_end_of_a_short_trail() {
+        // 50 long, the last two samples run on past its start
+        assert_eq!(
+            samples(&staircase(), 20.0, 5),
+            [
+                (30.0, 20.0, 'R'),
+                (20.0, 10.0, 'D'),
+                (10.0, 0.0, 'D'),
+                (-10.0, 0.0, 'R'),
+                (-30.0, 0.0, 'R')
+            ]
+        );
+
+        // A still snake faces up, its body straight down behind it
+        let trail = Trail::new(p(5.0, 5.0));
+        assert_eq!(
+            samples(&trail, 10.0, 3),
+            [(5.0, 5.0, 'U'), (5.0, 15.0, 'U'), (5.0, 25.0, 'U')]
+        );
+
+        // Turned but not moved yet, the body lines up behind the new heading
+        let mut trail = Trail::new(p(0.0, 0.0));
+        trail.turn(Direction::Left);
+        assert_eq!(
+            samples(&trail, 10.0, 2),
+            [(0.0, 0.0, 'L'), (10.0, 0.0, 'L')]
+        );
+        assert!(samples(&trail, 10.0, 0).is_empty());
+    }
+}
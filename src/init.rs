@@ -1,10 +1,21 @@
 use hyperfold_engine::{components, ecs::entities::EntityTrash};
 
+use input::Args;
+
 use crate::_engine::Events;
 
+/// The game to start is the first command line argument that isn't an option, defaulting to snake
 #[hyperfold_engine::system(Init)]
 fn init(events: &mut dyn Events) {
-    events.set_state(snake::Playing::Data);
+    match Args::get().game() {
+        Some("breakout") => events.set_state(breakout::Playing::Data),
+        Some("minesweeper") => events.set_state(minesweeper::Playing::Data),
+        Some("2048") => events.set_state(game2048::Playing::Data),
+        _ => {
+            snake::validate_assets();
+            events.set_state(snake::Playing::Data)
+        }
+    }
 }
 
 #[hyperfold_engine::event]
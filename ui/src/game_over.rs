@@ -0,0 +1,79 @@
+use hyperfold_engine::{
+    framework::render_system::{
+        render_data::RenderDataBuilderTrait, render_text::RenderText, AssetManager, Renderer,
+        Texture,
+    },
+    sdl2::SDL_Color,
+    utils::{
+        colors::WHITE,
+        rect::{Align, PointF},
+    },
+};
+
+use crate::text::{bake_text, draw_text, font_data};
+
+/// A line of text on a game over screen
+pub struct OverlayLine<'a> {
+    pub text: &'a str,
+    /// Sizes the font, so lines whose text changes keep one size
+    pub sample: &'a str,
+    /// Width `sample` is drawn at
+    pub w: u32,
+}
+
+impl<'a> OverlayLine<'a> {
+    /// Sized to its own text
+    pub fn new(text: &'a str, w: u32) -> Self {
+        Self {
+            text,
+            sample: text,
+            w,
+        }
+    }
+
+    pub fn with_sample(self, sample: &'a str) -> Self {
+        Self { sample, ..self }
+    }
+}
+
+/// The game over screen every game draws over its board
+pub struct GameOverText<'a> {
+    pub title: OverlayLine<'a>,
+    /// Below the title, e.g. the score and how to restart
+    pub lines: Vec<OverlayLine<'a>>,
+    /// Font file
+    pub font: String,
+    pub overlay: SDL_Color,
+}
+
+impl GameOverText<'_> {
+    /// Lines below the title are this fraction of the screen's height apart
+    pub const LINE_SPACING: f32 = 1.0 / 6.0;
+
+    /// The title ends just above the middle, each line is centered further down
+    pub fn texture(&self, w: u32, h: u32, r: &Renderer, am: &mut AssetManager) -> Texture {
+        let tex = Texture::new(r, w, h, self.overlay);
+        let (cx, cy) = (w as f32 / 2.0, h as f32 / 2.0);
+        let font = |line: &OverlayLine| font_data(&self.font, line.sample, line.w);
+
+        let rt = RenderText::new(font(&self.title))
+            .with_text(self.title.text)
+            .with_text_color(WHITE)
+            .with_dest_align(Align::Center, Align::BotRight);
+        bake_text(&tex, rt, PointF { x: cx, y: cy }, r, am);
+
+        for (i, line) in self.lines.iter().enumerate() {
+            let y = cy + (i as f32 + 0.5) * h as f32 * Self::LINE_SPACING;
+            draw_text(
+                &tex,
+                line.text,
+                font(line),
+                WHITE,
+                PointF { x: cx, y },
+                r,
+                am,
+            );
+        }
+        tex
+    }
+}
@@ -0,0 +1,5 @@
+pub mod game_over;
+pub mod text;
+
+pub use game_over::{GameOverText, OverlayLine};
+pub use text::{draw_text, font_data};
@@ -0,0 +1,46 @@
+use hyperfold_engine::{
+    framework::render_system::{
+        drawable::Canvas,
+        font::FontData,
+        render_data::{Fit, RenderDataBuilderTrait},
+        render_text::RenderText,
+        AssetManager, Renderer, Texture,
+    },
+    sdl2::SDL_Color,
+    utils::rect::{Align, PointF, Rect},
+};
+
+/// Font sized so that `sample` is `w` pixels wide
+pub fn font_data(file: &str, sample: &str, w: u32) -> FontData {
+    FontData {
+        w: Some(w),
+        h: None,
+        sample: sample.to_string(),
+        file: file.to_string(),
+    }
+}
+
+/// Renders `rt` onto `tex`, aligned on `pos` by its dest alignment
+pub(crate) fn bake_text(tex: &Texture, rt: RenderText, pos: PointF, r: &Renderer, am: &mut AssetManager) {
+    let rect = Rect::from_center(pos.x, pos.y, 0.0, 0.0);
+    let mut rt = rt.with_dest_fit(Fit::None).with_dest_rect(rect);
+    rt.render_text(rect, r, am);
+    tex.draw(r, &mut rt);
+}
+
+/// Bakes `text` onto `tex`, centered on `pos`
+pub fn draw_text(
+    tex: &Texture,
+    text: &str,
+    font: FontData,
+    color: SDL_Color,
+    pos: PointF,
+    r: &Renderer,
+    am: &mut AssetManager,
+) {
+    let rt = RenderText::new(font)
+        .with_text(text)
+        .with_text_color(color)
+        .with_dest_align(Align::Center, Align::Center);
+    bake_text(tex, rt, pos, r, am);
+}